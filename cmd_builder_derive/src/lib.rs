@@ -0,0 +1,340 @@
+//! # `#[derive(IntoCmd)]`
+//!
+//! Generates `impl From<&T> for cmd_builder::Cmd` for a struct holding options of an external
+//! tool invocation, use through the `derive` feature of `cmd_builder`
+//!
+//! Container attributes:
+//! ```text
+//! #[cmd(name = "git")]   - command name (default: struct name in kebab-case)
+//! ```
+//!
+//! Field attributes (exactly one per field):
+//! ```text
+//! #[cmd(short = 'a')]    - short flag or option (`-a`, `-a value`), `short` = first letter
+//! #[cmd(long = "name")]  - long flag or option (`--name`, `--name value`), `long` = field name
+//! #[cmd(param)]          - positional parameter
+//! #[cmd(env = "VAR")]    - environment variable
+//! #[cmd(subcommand)]     - subcommand, field type implements `From<&T> for Cmd`
+//! #[cmd(skip)]           - field is not used
+//! ```
+//!
+//! Field types:
+//! ```text
+//! bool      - flag added if `true` (env: `VAR=1` if `true`)
+//! Option<T> - skipped if `None`
+//! Vec<T>    - repeated for each item (`-I a -I b`)
+//! T         - always added
+//! ```
+//!
+//! Values are converted using `ToString`
+//!
+//! ```
+//! use cmd_builder::{Cmd, IntoCmd};
+//!
+//! #[derive(IntoCmd)]
+//! struct Ls {
+//!     #[cmd(short = 'l')]
+//!     long: bool,
+//!     #[cmd(param)]
+//!     paths: Vec<String>,
+//! }
+//!
+//! let ls = Ls { long: true, paths: vec!["src".to_string()] };
+//! assert_eq!(Cmd::from(&ls).to_string(), "ls -l src");
+//! ```
+//!
+//! # Compile errors
+//!
+//! Unknown attribute:
+//! ```compile_fail
+//! use cmd_builder::IntoCmd;
+//!
+//! #[derive(IntoCmd)]
+//! struct Ls {
+//!     #[cmd(flag)]
+//!     long: bool,
+//! }
+//! ```
+//!
+//! Parameter without `ToString`:
+//! ```compile_fail
+//! use cmd_builder::IntoCmd;
+//!
+//! struct Path;
+//!
+//! #[derive(IntoCmd)]
+//! struct Ls {
+//!     #[cmd(param)]
+//!     path: Path,
+//! }
+//! ```
+//!
+//! Conflicting roles (`short` and `long`):
+//! ```compile_fail
+//! use cmd_builder::IntoCmd;
+//!
+//! #[derive(IntoCmd)]
+//! struct Ls {
+//!     #[cmd(short, long)]
+//!     all: bool,
+//! }
+//! ```
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Field, Fields, GenericArgument, Ident,
+    LitChar, LitStr, PathArguments, Type,
+};
+
+/// Derive `From<&T> for Cmd`
+#[proc_macro_derive(IntoCmd, attributes(cmd))]
+pub fn derive_into_cmd(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+// role of the field in the command line
+enum Role {
+    Short(char),
+    Long(String),
+    Param,
+    Env(String),
+    Subcommand,
+    Skip,
+}
+
+// field type shape
+enum Kind {
+    Bool,
+    Option,
+    Vec,
+    Plain,
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "IntoCmd: only structs with named fields are supported",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "IntoCmd: only structs are supported",
+            ))
+        }
+    };
+
+    let name = container_name(input)?;
+    let statements = fields
+        .iter()
+        .map(field_statement)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let ident = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let mut generics = input.generics.clone();
+    generics.params.insert(0, parse_quote!('__a));
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::core::convert::From<&'__a #ident #ty_generics>
+            for ::cmd_builder::Cmd<'__a> #where_clause
+        {
+            fn from(value: &'__a #ident #ty_generics) -> Self {
+                let mut cmd = ::cmd_builder::Cmd::with_name(#name);
+                #(#statements)*
+                cmd
+            }
+        }
+    })
+}
+
+// `#[cmd(name = "...")]` or struct name in kebab-case
+fn container_name(input: &DeriveInput) -> syn::Result<String> {
+    let mut name = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cmd"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("IntoCmd: unknown container attribute, expected `name`"))
+            }
+        })?;
+    }
+    Ok(name.unwrap_or_else(|| kebab_case(&input.ident)))
+}
+
+// `MyTool` -> `my-tool`
+fn kebab_case(ident: &Ident) -> String {
+    let mut s = String::new();
+    for (i, c) in ident.to_string().chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                s.push('-');
+            }
+            s.extend(c.to_lowercase());
+        } else {
+            s.push(c);
+        }
+    }
+    s
+}
+
+fn field_role(field: &Field) -> syn::Result<Role> {
+    let ident = field.ident.as_ref().expect("named field");
+    let mut role = None;
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cmd"))
+    {
+        attr.parse_nested_meta(|meta| {
+            let parsed = if meta.path.is_ident("short") {
+                if meta.input.peek(syn::Token![=]) {
+                    Role::Short(meta.value()?.parse::<LitChar>()?.value())
+                } else {
+                    let c = ident.to_string().trim_start_matches('_').chars().next();
+                    Role::Short(c.ok_or_else(|| meta.error("IntoCmd: empty field name"))?)
+                }
+            } else if meta.path.is_ident("long") {
+                if meta.input.peek(syn::Token![=]) {
+                    Role::Long(meta.value()?.parse::<LitStr>()?.value())
+                } else {
+                    Role::Long(ident.to_string().replace('_', "-"))
+                }
+            } else if meta.path.is_ident("param") {
+                Role::Param
+            } else if meta.path.is_ident("env") {
+                Role::Env(meta.value()?.parse::<LitStr>()?.value())
+            } else if meta.path.is_ident("subcommand") {
+                Role::Subcommand
+            } else if meta.path.is_ident("skip") {
+                Role::Skip
+            } else {
+                return Err(meta.error(
+                    "IntoCmd: unknown field attribute, expected one of `short`, `long`, `param`, \
+                     `env`, `subcommand`, `skip`",
+                ));
+            };
+
+            if role.replace(parsed).is_some() {
+                return Err(meta.error("IntoCmd: only one role attribute per field is allowed"));
+            }
+            Ok(())
+        })?;
+    }
+
+    role.ok_or_else(|| {
+        syn::Error::new(
+            field.span(),
+            "IntoCmd: missing `#[cmd(...)]` attribute, use `#[cmd(skip)]` to ignore the field",
+        )
+    })
+}
+
+// single generic type argument (`Option<T>`, `Vec<T>`)
+fn has_type_argument(args: &PathArguments) -> bool {
+    match args {
+        PathArguments::AngleBracketed(args) => {
+            args.args.len() == 1 && matches!(args.args[0], GenericArgument::Type(_))
+        }
+        _ => false,
+    }
+}
+
+fn type_kind(ty: &Type) -> Kind {
+    if let Type::Path(path) = ty {
+        if let Some(segment) = path.path.segments.last() {
+            if segment.ident == "bool" && segment.arguments.is_empty() {
+                return Kind::Bool;
+            }
+            if has_type_argument(&segment.arguments) {
+                if segment.ident == "Option" {
+                    return Kind::Option;
+                }
+                if segment.ident == "Vec" {
+                    return Kind::Vec;
+                }
+            }
+        }
+    }
+    Kind::Plain
+}
+
+// builder calls for the field
+fn field_statement(field: &Field) -> syn::Result<TokenStream2> {
+    let ident = field.ident.as_ref().expect("named field");
+    let role = field_role(field)?;
+    let kind = type_kind(&field.ty);
+    let unsupported = |what: &str| {
+        Err(syn::Error::new(
+            field.ty.span(),
+            format!(
+                "IntoCmd: {} field can not be used as {}",
+                what,
+                role_name(&role)
+            ),
+        ))
+    };
+
+    // how to add a single value `v: &T`
+    let push = match &role {
+        Role::Short(c) => {
+            let key = format!("-{}", c);
+            quote! { cmd.push_option(#key, ::std::string::ToString::to_string(v)); }
+        }
+        Role::Long(name) => {
+            let key = format!("--{}", name);
+            quote! { cmd.push_option(#key, ::std::string::ToString::to_string(v)); }
+        }
+        Role::Param => quote! { cmd.push_param(::std::string::ToString::to_string(v)); },
+        Role::Env(var) => quote! { cmd.env(#var, ::std::string::ToString::to_string(v)); },
+        Role::Subcommand => quote! { cmd.push_cmd(::cmd_builder::Cmd::from(v)); },
+        Role::Skip => return Ok(TokenStream2::new()),
+    };
+
+    Ok(match kind {
+        Kind::Bool => match &role {
+            Role::Short(c) => quote! { if value.#ident { cmd.push_flag_short(#c); } },
+            Role::Long(name) => {
+                let flag = format!("--{}", name);
+                quote! { if value.#ident { cmd.push_flag(#flag); } }
+            }
+            Role::Env(var) => quote! { if value.#ident { cmd.env(#var, "1"); } },
+            _ => return unsupported("`bool`"),
+        },
+        Kind::Option => quote! { if let ::core::option::Option::Some(v) = &value.#ident { #push } },
+        Kind::Vec => match &role {
+            Role::Env(_) => return unsupported("`Vec`"),
+            _ => quote! { for v in &value.#ident { #push } },
+        },
+        Kind::Plain => quote! { { let v = &value.#ident; #push } },
+    })
+}
+
+fn role_name(role: &Role) -> &'static str {
+    match role {
+        Role::Short(_) => "`short`",
+        Role::Long(_) => "`long`",
+        Role::Param => "`param`",
+        Role::Env(_) => "`env`",
+        Role::Subcommand => "`subcommand`",
+        Role::Skip => "`skip`",
+    }
+}
@@ -0,0 +1,149 @@
+use std::borrow::Cow;
+
+/// flags, args separator (double hyphen)
+pub const FLAGS_ARGS_SEPARATOR: &str = "--";
+
+/// Option key and value joining style
+///
+/// ```text
+/// Separate - two arguments (`-t value`, `--target value`)
+/// Equals   - one argument joined by equals sign (`--target=value`)
+/// Attached - one argument, value directly after key (`-Ivalue`)
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum OptionStyle {
+    #[default]
+    Separate,
+    Equals,
+    Attached,
+}
+
+/// Single command line argument, keeping the role of each token
+///
+/// ```text
+/// Flag      - flag, short or long (`-a`, `--all`)
+/// Option    - option, flag and value (`-t value`, `--target=value`, `-Ivalue`)
+/// Param     - parameter, positional value (`file`)
+/// Separator - flags, args separator (`--`)
+/// Raw       - argument with unknown role, passed as is
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Arg<'a> {
+    Flag(Cow<'a, str>),
+    Option {
+        key: Cow<'a, str>,
+        value: Cow<'a, str>,
+        /// option style, `None` = `Cmd.option_style`
+        style: Option<OptionStyle>,
+    },
+    Param(Cow<'a, str>),
+    Separator,
+    Raw(Cow<'a, str>),
+}
+
+impl<'a> Arg<'a> {
+    /// Create `Arg::Flag`
+    pub fn flag<S: Into<Cow<'a, str>>>(flag: S) -> Self {
+        Arg::Flag(flag.into())
+    }
+
+    /// Create `Arg::Option` with default style
+    pub fn option<T, U>(key: T, value: U) -> Self
+    where
+        T: Into<Cow<'a, str>>,
+        U: Into<Cow<'a, str>>,
+    {
+        Arg::Option {
+            key: key.into(),
+            value: value.into(),
+            style: None,
+        }
+    }
+
+    /// Create `Arg::Option` with given style
+    pub fn option_ext<T, U>(key: T, value: U, style: OptionStyle) -> Self
+    where
+        T: Into<Cow<'a, str>>,
+        U: Into<Cow<'a, str>>,
+    {
+        Arg::Option {
+            key: key.into(),
+            value: value.into(),
+            style: Some(style),
+        }
+    }
+
+    /// Create `Arg::Param`
+    pub fn param<S: Into<Cow<'a, str>>>(param: S) -> Self {
+        Arg::Param(param.into())
+    }
+
+    /// Create `Arg::Raw`
+    pub fn raw<S: Into<Cow<'a, str>>>(raw: S) -> Self {
+        Arg::Raw(raw.into())
+    }
+
+    /// Convert to `Arg` owning all strings
+    pub fn into_owned(self) -> Arg<'static> {
+        match self {
+            Arg::Flag(flag) => Arg::Flag(Cow::Owned(flag.into_owned())),
+            Arg::Option { key, value, style } => Arg::Option {
+                key: Cow::Owned(key.into_owned()),
+                value: Cow::Owned(value.into_owned()),
+                style,
+            },
+            Arg::Param(param) => Arg::Param(Cow::Owned(param.into_owned())),
+            Arg::Separator => Arg::Separator,
+            Arg::Raw(raw) => Arg::Raw(Cow::Owned(raw.into_owned())),
+        }
+    }
+
+    /// Returns `true` if argument is a positional parameter
+    pub fn is_param(&self) -> bool {
+        matches!(self, Arg::Param(_))
+    }
+
+    /// Transform `Arg` to `Vec<Cow<'a, str>>`, option with separate style results in two
+    /// elements, all other arguments in one
+    ///
+    /// # Examples
+    /// ```
+    /// use cmd_builder::arg::{Arg, OptionStyle};
+    ///
+    /// assert_eq!(Arg::option("-t", "x").to_vec(), vec!["-t", "x"]);
+    /// assert_eq!(Arg::Separator.to_vec(), vec!["--"]);
+    /// let arg = Arg::Option {
+    ///     key: "--target".into(),
+    ///     value: "x".into(),
+    ///     style: Some(OptionStyle::Equals),
+    /// };
+    /// assert_eq!(arg.to_vec(), vec!["--target=x"]);
+    /// ```
+    pub fn to_vec(&self) -> Vec<Cow<'a, str>> {
+        self.to_vec_ext(OptionStyle::default())
+    }
+
+    /// Transform `Arg` to `Vec<Cow<'a, str>>`, options without own style are rendered using
+    /// given `style`
+    pub fn to_vec_ext(&self, style: OptionStyle) -> Vec<Cow<'a, str>> {
+        match self {
+            Arg::Flag(flag) => vec![flag.clone()],
+            Arg::Option {
+                key,
+                value,
+                style: option_style,
+            } => match option_style.unwrap_or(style) {
+                OptionStyle::Separate => vec![key.clone(), value.clone()],
+                OptionStyle::Equals => vec![Cow::Owned(format!("{}={}", key, value))],
+                OptionStyle::Attached => vec![Cow::Owned(format!("{}{}", key, value))],
+            },
+            Arg::Param(param) => vec![param.clone()],
+            Arg::Separator => vec![Cow::Borrowed(FLAGS_ARGS_SEPARATOR)],
+            Arg::Raw(raw) => vec![raw.clone()],
+        }
+    }
+}
@@ -0,0 +1,220 @@
+// `cc -c -o main.o -Iinclude --std=c99 -- -main.c -O2`
+fn typed_args_cmd() -> crate::Cmd<'static> {
+    use crate::{Arg, Cmd, OptionStyle};
+
+    let mut cmd = Cmd::with_name("cc");
+    cmd.push_flag("-c")
+        .push_option("-o", "main.o")
+        .push_arg(Arg::Option {
+            key: "-I".into(),
+            value: "include".into(),
+            style: Some(OptionStyle::Attached),
+        })
+        .push_arg(Arg::Option {
+            key: "--std".into(),
+            value: "c99".into(),
+            style: Some(OptionStyle::Equals),
+        })
+        .push_arg(Arg::Separator)
+        .push_param("-main.c")
+        .push_raw("-O2");
+    cmd
+}
+
+#[test]
+fn typed_args_roles() {
+    use crate::Arg;
+
+    let cmd = typed_args_cmd();
+    let args = cmd.args.as_deref().unwrap();
+    assert_eq!(
+        args[1],
+        Arg::Option {
+            key: "-o".into(),
+            value: "main.o".into(),
+            style: None
+        }
+    );
+    assert_eq!(args.iter().filter(|arg| arg.is_param()).count(), 1);
+}
+
+#[test]
+fn typed_args_to_vec() {
+    assert_eq!(
+        typed_args_cmd().to_vec(),
+        vec![
+            "cc",
+            "-c",
+            "-o",
+            "main.o",
+            "-Iinclude",
+            "--std=c99",
+            "--",
+            "-main.c",
+            "-O2"
+        ]
+    );
+}
+
+#[test]
+fn typed_args_to_command() {
+    assert_eq!(
+        typed_args_cmd().to_command().get_args().collect::<Vec<_>>(),
+        vec![
+            "-c",
+            "-o",
+            "main.o",
+            "-Iinclude",
+            "--std=c99",
+            "--",
+            "-main.c",
+            "-O2"
+        ]
+    );
+}
+
+#[test]
+fn flags_args_separator_not_set() {
+    use crate::Cmd;
+
+    let cmd = Cmd::with_name("rm")
+        .push_flag("-f")
+        .push_param("-rf")
+        .push_param("file")
+        .to_owned();
+    assert_eq!(cmd.to_vec(), vec!["rm", "-f", "-rf", "file"]);
+}
+
+#[test]
+fn flags_args_separator_auto() {
+    use crate::Cmd;
+
+    let mut cmd = Cmd::with_name("rm");
+    cmd.push_flag("-f")
+        .push_param("-rf")
+        .push_param("file")
+        .auto_flags_args_separator();
+    assert_eq!(cmd.to_vec(), vec!["rm", "-f", "--", "-rf", "file"]);
+    assert_eq!(cmd.to_string(), "rm -f -- -rf file");
+    assert_eq!(
+        cmd.to_command().get_args().collect::<Vec<_>>(),
+        vec!["-f", "--", "-rf", "file"]
+    );
+}
+
+#[test]
+fn flags_args_separator_auto_not_needed() {
+    use crate::Cmd;
+
+    // no params beginning with hyphen
+    let mut cmd = Cmd::with_name("rm");
+    cmd.auto_flags_args_separator()
+        .push_flag("-f")
+        .push_param("file");
+    assert_eq!(cmd.to_vec(), vec!["rm", "-f", "file"]);
+}
+
+#[test]
+fn flags_args_separator_always() {
+    use crate::Cmd;
+
+    let mut cmd = Cmd::with_name("rm");
+    cmd.push_flag("-f")
+        .push_param("file")
+        .flags_args_separator("--");
+    assert_eq!(cmd.to_vec(), vec!["rm", "-f", "--", "file"]);
+
+    // even without params
+    let mut cmd = Cmd::with_name("git");
+    cmd.push_flag("--all").flags_args_separator("--");
+    assert_eq!(cmd.to_vec(), vec!["git", "--all", "--"]);
+}
+
+#[test]
+fn flags_args_separator_keeps_order() {
+    use crate::Cmd;
+
+    // inserted before the first parameter beginning with hyphen, nothing is moved
+    let mut cmd = Cmd::with_name("grep");
+    cmd.push_flag("-i")
+        .push_param("pattern")
+        .push_param("-x")
+        .push_option("-m", "1")
+        .auto_flags_args_separator();
+    assert_eq!(
+        cmd.to_vec(),
+        vec!["grep", "-i", "pattern", "--", "-x", "-m", "1"]
+    );
+}
+
+#[test]
+fn flags_args_separator_after_options() {
+    use crate::Cmd;
+
+    // options before a plain parameter stay where they are
+    let mut cmd = Cmd::with_name("git");
+    cmd.push_option("-m", "x").push_param("commit");
+    cmd.auto_flags_args_separator();
+    assert_eq!(cmd.to_vec(), vec!["git", "-m", "x", "commit"]);
+    cmd.not_auto_flags_args_separator()
+        .flags_args_separator("--");
+    assert_eq!(cmd.to_vec(), vec!["git", "-m", "x", "--", "commit"]);
+}
+
+// git with Cmd-wide `Equals` style and per-option `Separate` and `Attached` styles
+fn option_style_cmd() -> crate::Cmd<'static> {
+    use crate::{Cmd, OptionStyle};
+
+    let mut cmd = Cmd::with_name("git");
+    cmd.option_style(OptionStyle::Equals)
+        .push_param("commit")
+        .push_option("--message", "fix: it's")
+        .push_option_ext("-C", "/repo", OptionStyle::Separate)
+        .push_option_ext("-c", "user.name=me", OptionStyle::Attached);
+    cmd
+}
+
+// argv of `option_style_cmd()` after the program
+const OPTION_STYLE_ARGV: [&str; 5] = [
+    "commit",
+    "--message=fix: it's",
+    "-C",
+    "/repo",
+    "-cuser.name=me",
+];
+
+#[test]
+fn option_style() {
+    assert_eq!(option_style_cmd().to_vec()[1..], OPTION_STYLE_ARGV);
+}
+
+#[test]
+fn option_style_to_string() {
+    assert_eq!(
+        option_style_cmd().to_string(),
+        "git commit '--message=fix: it'\\''s' -C /repo -cuser.name=me"
+    );
+}
+
+#[test]
+fn option_style_to_command() {
+    assert_eq!(
+        option_style_cmd()
+            .to_command()
+            .get_args()
+            .collect::<Vec<_>>(),
+        OPTION_STYLE_ARGV
+    );
+}
+
+#[test]
+fn option_style_default() {
+    use crate::{Cmd, OptionStyle};
+
+    // options without own style follow the Cmd-wide style when rendered
+    let mut cmd = Cmd::with_name("tmux");
+    cmd.push_option("-t", "main");
+    assert_eq!(cmd.to_string(), "tmux -t main");
+    cmd.option_style(OptionStyle::Attached);
+    assert_eq!(cmd.to_string(), "tmux -tmain");
+}
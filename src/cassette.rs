@@ -0,0 +1,519 @@
+//! Record and replay of command execution ([`Recorder`], [`Replayer`]) using cassette files
+//!
+//! Cassette is a text file, one entry per executed command, entries are separated by an empty
+//! line. Command lines are POSIX sh quoted (words with control characters use dollar single
+//! quotes, `$'a\nb'`), output is escaped (`\\`, `\n`, `\r`, `\t`, `\xNN` for other control
+//! characters and invalid UTF-8), empty output is omitted. Process terminated by a signal has
+//! `signal: N` line instead of `code: N`:
+//!
+//! ```text
+//! # cmd_builder cassette
+//! $ tmux has-session -t main
+//! env: LANG=C
+//! cwd: /tmp
+//! code: 1
+//! stderr: can't find session: main\n
+//!
+//! $ tmux list-sessions -F '#S'
+//! code: 0
+//! stdout: main\nwork\n
+//! ```
+use super::exec::{CmdError, CmdOutput};
+use super::executor::Executor;
+use super::parse::split_posix;
+use super::quote::{is_posix_name, posix_quote};
+use super::Cmd;
+use std::borrow::Cow;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Mutex, PoisonError};
+
+const HEADER: &str = "# cmd_builder cassette";
+const COMMAND_PREFIX: &str = "$ ";
+const ENV_PREFIX: &str = "env: ";
+const CWD_PREFIX: &str = "cwd: ";
+const CODE_PREFIX: &str = "code: ";
+const SIGNAL_PREFIX: &str = "signal: ";
+const STDOUT_PREFIX: &str = "stdout: ";
+const STDERR_PREFIX: &str = "stderr: ";
+
+/// Recorded command execution: command (argv, environment variables, working directory) and
+/// its result (exit code, stdout, stderr)
+#[derive(Debug, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct CassetteEntry {
+    /// rendered program and arguments (see [`Cmd::to_argv()`])
+    pub argv: Vec<String>,
+
+    /// environment variables (see [`Cmd::to_envs()`]), sorted by key
+    pub envs: Vec<(String, String)>,
+
+    /// working directory
+    pub current_dir: Option<String>,
+
+    /// exit code, `0` if terminated by a signal
+    pub code: i32,
+
+    /// signal which terminated the process (unix)
+    pub signal: Option<i32>,
+
+    /// captured standard output
+    pub stdout: Vec<u8>,
+
+    /// captured standard error
+    pub stderr: Vec<u8>,
+}
+
+impl CassetteEntry {
+    /// Create `CassetteEntry` from an executed command and its output
+    pub fn new(cmd: &Cmd, output: &CmdOutput) -> Self {
+        let mut entry = CassetteEntry::from_cmd(cmd);
+        entry.code = output.code().unwrap_or_default();
+        entry.signal = signal(output);
+        entry.stdout = output.stdout.clone();
+        entry.stderr = output.stderr.clone();
+        entry
+    }
+
+    // command part of the entry, no output
+    fn from_cmd(cmd: &Cmd) -> Self {
+        let mut envs: Vec<(String, String)> = cmd
+            .to_envs()
+            .into_iter()
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect();
+        envs.sort();
+
+        CassetteEntry {
+            argv: cmd
+                .to_argv()
+                .into_iter()
+                .map(|arg| arg.into_owned())
+                .collect(),
+            envs,
+            current_dir: cmd.current_dir.as_ref().map(|dir| dir.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Returns `true` if the command (argv, environment variables, working directory) is the
+    /// same as the recorded one
+    pub fn matches(&self, cmd: &Cmd) -> bool {
+        self.command_lines() == CassetteEntry::from_cmd(cmd).command_lines()
+    }
+
+    /// Recorded output
+    pub fn to_output(&self) -> CmdOutput {
+        let output = CmdOutput::from_code(self.code, self.stdout.clone(), self.stderr.clone());
+        #[cfg(unix)]
+        if let Some(signal) = self.signal {
+            use std::os::unix::process::ExitStatusExt;
+            use std::process::ExitStatus;
+            return CmdOutput {
+                status: ExitStatus::from_raw(signal),
+                ..output
+            };
+        }
+        output
+    }
+
+    // `$ argv`, `env: KEY=VALUE`, `cwd: DIR` lines
+    fn command_lines(&self) -> Vec<String> {
+        let argv: Vec<_> = self.argv.iter().map(|arg| quote(arg)).collect();
+        let mut lines = vec![format!("{}{}", COMMAND_PREFIX, argv.join(" "))];
+        for (key, value) in &self.envs {
+            let env = if is_posix_name(key) {
+                format!("{}={}", key, quote(value))
+            } else {
+                quote(&format!("{}={}", key, value)).into_owned()
+            };
+            lines.push(format!("{}{}", ENV_PREFIX, env));
+        }
+        if let Some(dir) = &self.current_dir {
+            lines.push(format!("{}{}", CWD_PREFIX, quote(dir)));
+        }
+        lines
+    }
+}
+
+impl fmt::Display for CassetteEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for line in self.command_lines() {
+            writeln!(f, "{}", line)?;
+        }
+        match self.signal {
+            Some(signal) => writeln!(f, "{}{}", SIGNAL_PREFIX, signal)?,
+            None => writeln!(f, "{}{}", CODE_PREFIX, self.code)?,
+        }
+        if !self.stdout.is_empty() {
+            writeln!(f, "{}{}", STDOUT_PREFIX, escape(&self.stdout))?;
+        }
+        if !self.stderr.is_empty() {
+            writeln!(f, "{}{}", STDERR_PREFIX, escape(&self.stderr))?;
+        }
+        Ok(())
+    }
+}
+
+/// Recorded commands in execution order, text format is described in the [module
+/// documentation][`crate::cassette`] (`Display`, `FromStr`)
+#[derive(Debug, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Cassette {
+    pub entries: Vec<CassetteEntry>,
+}
+
+impl Cassette {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read cassette from file, invalid content is an `InvalidData` error
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    /// Write cassette to file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+}
+
+impl fmt::Display for Cassette {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+        for (i, entry) in self.entries.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", entry)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Cassette {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut entries = Vec::new();
+        let mut entry: Option<CassetteEntry> = None;
+
+        for (i, line) in s.lines().enumerate() {
+            let invalid = |message: &str| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("cassette line {}: {}", i + 1, message),
+                )
+            };
+            let words = |s: &str| {
+                split_posix(s)
+                    .map(|words| words.into_iter().map(|word| word.value).collect::<Vec<_>>())
+                    .map_err(|err| invalid(&err.to_string()))
+            };
+
+            if line.is_empty() || line.starts_with('#') {
+                entries.extend(entry.take());
+                continue;
+            }
+            if let Some(command) = line.strip_prefix(COMMAND_PREFIX) {
+                entries.extend(entry.take());
+                entry = Some(CassetteEntry {
+                    argv: words(command)?,
+                    ..Default::default()
+                });
+                continue;
+            }
+
+            let entry = entry
+                .as_mut()
+                .ok_or_else(|| invalid("expected command (`$ ...`)"))?;
+            if let Some(env) = line.strip_prefix(ENV_PREFIX) {
+                for word in words(env)? {
+                    let (key, value) = word
+                        .split_once('=')
+                        .ok_or_else(|| invalid("expected `KEY=VALUE`"))?;
+                    entry.envs.push((key.to_string(), value.to_string()));
+                }
+                entry.envs.sort();
+            } else if let Some(dir) = line.strip_prefix(CWD_PREFIX) {
+                entry.current_dir = Some(words(dir)?.concat());
+            } else if let Some(code) = line.strip_prefix(CODE_PREFIX) {
+                entry.code = code.parse().map_err(|_| invalid("invalid exit code"))?;
+            } else if let Some(signal) = line.strip_prefix(SIGNAL_PREFIX) {
+                let signal = signal.parse().map_err(|_| invalid("invalid signal"))?;
+                entry.signal = Some(signal);
+            } else if let Some(stdout) = line.strip_prefix(STDOUT_PREFIX) {
+                entry.stdout = unescape(stdout).ok_or_else(|| invalid("invalid escape"))?;
+            } else if let Some(stderr) = line.strip_prefix(STDERR_PREFIX) {
+                entry.stderr = unescape(stderr).ok_or_else(|| invalid("invalid escape"))?;
+            } else {
+                return Err(invalid("unknown line"));
+            }
+        }
+        entries.extend(entry);
+
+        Ok(Cassette { entries })
+    }
+}
+
+// signal which terminated the process
+#[cfg(unix)]
+fn signal(output: &CmdOutput) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    output.status.signal()
+}
+
+// signal which terminated the process, only unix processes are terminated by signals
+#[cfg(not(unix))]
+fn signal(_output: &CmdOutput) -> Option<i32> {
+    None
+}
+
+// single line POSIX sh word, words with control characters use dollar single quotes
+fn quote(word: &str) -> Cow<'_, str> {
+    if word.contains(char::is_control) {
+        Cow::Owned(format!(
+            "$'{}'",
+            escape(word.as_bytes()).replace('\'', "\\'")
+        ))
+    } else {
+        posix_quote(word)
+    }
+}
+
+// output as a single line, control characters and invalid UTF-8 are escaped
+fn escape(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(bytes.len());
+    for chunk in bytes.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            match c {
+                '\\' => s.push_str("\\\\"),
+                '\n' => s.push_str("\\n"),
+                '\r' => s.push_str("\\r"),
+                '\t' => s.push_str("\\t"),
+                c if c.is_control() => {
+                    let mut buf = [0; 4];
+                    for b in c.encode_utf8(&mut buf).bytes() {
+                        s.push_str(&format!("\\x{:02x}", b));
+                    }
+                }
+                c => s.push(c),
+            }
+        }
+        for b in chunk.invalid() {
+            s.push_str(&format!("\\x{:02x}", b));
+        }
+    }
+    s
+}
+
+// reverse of `escape()`, `None` if escape sequence is invalid
+fn unescape(s: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+        match chars.next()? {
+            '\\' => bytes.push(b'\\'),
+            'n' => bytes.push(b'\n'),
+            'r' => bytes.push(b'\r'),
+            't' => bytes.push(b'\t'),
+            'x' => {
+                let hex: String = chars.by_ref().take(2).collect();
+                bytes.push(
+                    u8::from_str_radix(&hex, 16)
+                        .ok()
+                        .filter(|_| hex.len() == 2)?,
+                );
+            }
+            _ => return None,
+        }
+    }
+    Some(bytes)
+}
+
+// line diff (longest common subsequence), unchanged lines prefixed with `  `, removed with `- `,
+// added with `+ `
+fn diff_lines(expected: &[String], actual: &[String]) -> String {
+    let (n, m) = (expected.len(), actual.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if expected[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && expected[i] == actual[j] {
+            lines.push(format!("  {}", expected[i]));
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(format!("- {}", expected[i]));
+            i += 1;
+        } else {
+            lines.push(format!("+ {}", actual[j]));
+            j += 1;
+        }
+    }
+    lines.join("\n")
+}
+
+/// Executor recording commands executed by the wrapped executor into a [`Cassette`], results
+/// are passed through unchanged, commands which could not be executed (not [`CmdError::Failed`]
+/// errors) are not recorded
+///
+/// # Examples
+/// ```no_run
+/// use cmd_builder::cassette::Recorder;
+/// use cmd_builder::executor::{Executor, RealExecutor};
+/// use cmd_builder::Cmd;
+///
+/// let recorder = Recorder::new(RealExecutor);
+/// recorder.execute(Cmd::with_name("tmux").param("list-sessions")).unwrap();
+/// recorder.cassette().save("tests/cassettes/tmux.cassette").unwrap();
+/// ```
+#[derive(Debug, Default)]
+pub struct Recorder<E> {
+    executor: E,
+    cassette: Mutex<Cassette>,
+}
+
+impl<E: Executor> Recorder<E> {
+    pub fn new(executor: E) -> Self {
+        Recorder {
+            executor,
+            cassette: Mutex::new(Cassette::new()),
+        }
+    }
+
+    /// Recorded cassette
+    pub fn cassette(&self) -> Cassette {
+        self.cassette
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl<E: Executor> Executor for Recorder<E> {
+    fn execute(&self, cmd: &Cmd) -> Result<CmdOutput, CmdError> {
+        let result = self.executor.execute(cmd);
+        let output = match &result {
+            Ok(output) | Err(CmdError::Failed { output, .. }) => output,
+            Err(_) => return result,
+        };
+        self.cassette
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entries
+            .push(CassetteEntry::new(cmd, output));
+        result
+    }
+}
+
+/// Executor serving results recorded in a [`Cassette`], each entry is used once, the first
+/// unused entry matching the command (see [`CassetteEntry::matches()`]) is served. Command
+/// without matching entry fails with [`CmdError::Replay`] containing the diff against the
+/// closest unused entry (the first one with the same program, or the first one)
+///
+/// # Examples
+/// ```
+/// use cmd_builder::cassette::Replayer;
+/// use cmd_builder::executor::Executor;
+/// use cmd_builder::Cmd;
+///
+/// let cassette = "$ tmux list-sessions -F '#S'\ncode: 0\nstdout: main\\nwork\\n\n";
+/// let replayer = Replayer::new(cassette.parse().unwrap());
+///
+/// let mut cmd = Cmd::with_name("tmux");
+/// cmd.param("list-sessions").opt("-F", "#S");
+/// assert_eq!(replayer.execute(&cmd).unwrap().stdout, b"main\nwork\n");
+///
+/// let err = replayer.execute(&cmd).unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     "`tmux list-sessions -F '#S'`: no recorded command matches\n\
+///      + $ tmux list-sessions -F '#S'"
+/// );
+/// ```
+#[derive(Debug)]
+pub struct Replayer {
+    cassette: Cassette,
+    used: Mutex<Vec<bool>>,
+}
+
+impl Replayer {
+    pub fn new(cassette: Cassette) -> Self {
+        Replayer {
+            used: Mutex::new(vec![false; cassette.entries.len()]),
+            cassette,
+        }
+    }
+
+    /// Read cassette from file (see [`Cassette::load()`])
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Replayer::new(Cassette::load(path)?))
+    }
+
+    /// Recorded entries not served yet
+    pub fn unused(&self) -> Vec<&CassetteEntry> {
+        let used = self.used.lock().unwrap_or_else(PoisonError::into_inner);
+        self.cassette
+            .entries
+            .iter()
+            .zip(used.iter())
+            .filter(|(_, used)| !**used)
+            .map(|(entry, _)| entry)
+            .collect()
+    }
+}
+
+impl Executor for Replayer {
+    fn execute(&self, cmd: &Cmd) -> Result<CmdOutput, CmdError> {
+        let mut used = self.used.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut unused = self
+            .cassette
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !used[*i]);
+
+        let Some((i, entry)) = unused.clone().find(|(_, entry)| entry.matches(cmd)) else {
+            let actual = CassetteEntry::from_cmd(cmd);
+            let closest = unused
+                .clone()
+                .find(|(_, entry)| entry.argv.first() == actual.argv.first())
+                .or_else(|| unused.next())
+                .map(|(_, entry)| entry.command_lines())
+                .unwrap_or_default();
+            return Err(CmdError::Replay {
+                command: cmd.to_string(),
+                diff: diff_lines(&closest, &actual.command_lines()),
+            });
+        };
+
+        used[i] = true;
+        let output = entry.to_output();
+        if output.success() {
+            Ok(output)
+        } else {
+            Err(CmdError::Failed {
+                command: cmd.to_string(),
+                output,
+            })
+        }
+    }
+}
@@ -0,0 +1,305 @@
+#[test]
+fn cassette_format() {
+    use crate::cassette::{Cassette, CassetteEntry};
+    use crate::{Cmd, CmdOutput};
+
+    let mut cmd = Cmd::with_name("tmux");
+    cmd.env("LANG", "C")
+        .current_dir("/tmp/my dir")
+        .param("list-sessions")
+        .opt("-F", "#S: #{session_windows}");
+
+    let cassette = Cassette {
+        entries: vec![
+            CassetteEntry::new(
+                &cmd,
+                &CmdOutput::from_code(0, "main: 1\n\tx\\y\n", b"\xff\x1b".to_vec()),
+            ),
+            CassetteEntry::new(
+                Cmd::with_name("tmux").param("kill-server"),
+                &CmdOutput::from_code(1, "", "no server running\n"),
+            ),
+        ],
+    };
+
+    let s = cassette.to_string();
+    assert_eq!(
+        s,
+        "# cmd_builder cassette\n\
+         $ tmux list-sessions -F '#S: #{session_windows}'\n\
+         env: LANG=C\n\
+         cwd: '/tmp/my dir'\n\
+         code: 0\n\
+         stdout: main: 1\\n\\tx\\\\y\\n\n\
+         stderr: \\xff\\x1b\n\
+         \n\
+         $ tmux kill-server\n\
+         code: 1\n\
+         stderr: no server running\\n\n"
+    );
+    assert_eq!(s.parse::<Cassette>().unwrap(), cassette);
+}
+
+#[test]
+fn cassette_format_control_characters() {
+    use crate::cassette::{Cassette, CassetteEntry};
+    use crate::{Cmd, CmdOutput};
+
+    // control characters of the command are escaped, each field is a single line
+    let mut cmd = Cmd::with_name("sh");
+    cmd.env("A B", "x\ty")
+        .current_dir("a\nb")
+        .opt("-c", "echo 'a'\necho \\b");
+    let cassette = Cassette {
+        entries: vec![CassetteEntry::new(&cmd, &CmdOutput::from_code(0, "", ""))],
+    };
+    let s = cassette.to_string();
+    assert_eq!(
+        s,
+        "# cmd_builder cassette\n\
+         $ sh -c $'echo \\'a\\'\\necho \\\\b'\n\
+         env: $'A B=x\\ty'\n\
+         cwd: $'a\\nb'\n\
+         code: 0\n"
+    );
+    assert_eq!(s.parse::<Cassette>().unwrap(), cassette);
+}
+
+#[test]
+fn cassette_parse_missing_command() {
+    use crate::cassette::Cassette;
+
+    let err = "code: 0\n".parse::<Cassette>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "cassette line 1: expected command (`$ ...`)"
+    );
+}
+
+#[test]
+fn cassette_parse_invalid_escape() {
+    use crate::cassette::Cassette;
+
+    let err = "$ ls\nstdout: \\q\n".parse::<Cassette>().unwrap_err();
+    assert_eq!(err.to_string(), "cassette line 2: invalid escape");
+}
+
+// `has-session -t main` failing, then `new-session -d -s main`, both with `LANG=C`
+fn tmux_replayer() -> crate::cassette::Replayer {
+    use crate::cassette::Replayer;
+
+    let cassette = "# cmd_builder cassette\n\
+                    $ tmux has-session -t main\n\
+                    env: LANG=C\n\
+                    code: 1\n\
+                    stderr: can't find session: main\\n\n\
+                    \n\
+                    $ tmux new-session -d -s main\n\
+                    env: LANG=C\n\
+                    code: 0\n";
+    Replayer::new(cassette.parse().unwrap())
+}
+
+// tmux command with `LANG=C` environment
+fn tmux_lang(args: &[&'static str]) -> crate::Cmd<'static> {
+    use crate::Cmd;
+
+    let mut cmd = Cmd::with_name("tmux");
+    cmd.env("LANG", "C");
+    for arg in args {
+        cmd.push_raw(*arg);
+    }
+    cmd
+}
+
+#[test]
+fn cassette_replay_mismatch() {
+    use crate::executor::Executor;
+    use crate::CmdError;
+
+    // wrong session name, different environment
+    let mut cmd = tmux_lang(&["new-session", "-d", "-s", "work"]);
+    cmd.env("TERM", "xterm");
+    match tmux_replayer().execute(&cmd) {
+        Err(CmdError::Replay { diff, .. }) => assert_eq!(
+            diff,
+            "- $ tmux has-session -t main\n\
+             + $ tmux new-session -d -s work\n  \
+             env: LANG=C\n\
+             + env: TERM=xterm"
+        ),
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn cassette_replay_failed() {
+    use crate::executor::Executor;
+
+    let err = tmux_replayer()
+        .execute(&tmux_lang(&["has-session", "-t", "main"]))
+        .unwrap_err();
+    assert_eq!(err.code(), Some(1));
+    assert_eq!(err.stderr().as_deref(), Some("can't find session: main\n"));
+}
+
+#[test]
+fn cassette_replay_used_entry() {
+    use crate::executor::Executor;
+    use crate::CmdError;
+
+    let replayer = tmux_replayer();
+    let cmd = tmux_lang(&["has-session", "-t", "main"]);
+    assert_eq!(replayer.execute(&cmd).unwrap_err().code(), Some(1));
+
+    // already used entry is not served again, closest entry has the same program
+    match replayer.execute(&cmd) {
+        Err(CmdError::Replay { diff, .. }) => assert_eq!(
+            diff,
+            "- $ tmux new-session -d -s main\n\
+             + $ tmux has-session -t main\n  \
+             env: LANG=C"
+        ),
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn cassette_replay_unused() {
+    use crate::executor::Executor;
+
+    let replayer = tmux_replayer();
+    assert_eq!(replayer.unused().len(), 2);
+    // entries are matched regardless of their order
+    assert!(replayer
+        .execute(&tmux_lang(&["new-session", "-d", "-s", "main"]))
+        .is_ok());
+    assert_eq!(replayer.unused().len(), 1);
+    assert!(replayer
+        .execute(&tmux_lang(&["has-session", "-t", "main"]))
+        .is_err());
+    assert!(replayer.unused().is_empty());
+}
+
+// records `cmds` with the real executor, saves the cassette to a file and loads it back,
+// returns the recorded results, the cassette file and its replayer
+#[cfg(unix)]
+fn record_cassette(
+    name: &str,
+    cmds: &[&crate::Cmd],
+) -> (
+    Vec<Result<crate::CmdOutput, crate::CmdError>>,
+    String,
+    crate::cassette::Replayer,
+) {
+    use crate::cassette::{Recorder, Replayer};
+    use crate::executor::{Executor, RealExecutor};
+    use std::fs;
+
+    let dir = std::env::temp_dir().join(format!(
+        "cmd_builder_cassette_{}_{}",
+        name,
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("sh.cassette");
+
+    let recorder = Recorder::new(RealExecutor);
+    let results = cmds.iter().map(|cmd| recorder.execute(cmd)).collect();
+    recorder.cassette().save(&path).unwrap();
+    let file = fs::read_to_string(&path).unwrap();
+    let replayer = Replayer::load(&path).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    (results, file, replayer)
+}
+
+#[cfg(unix)]
+#[test]
+fn cassette_record_output() {
+    use crate::executor::Executor;
+    use crate::Cmd;
+
+    let mut ok = Cmd::with_name("sh");
+    ok.env("GREETING", "hello")
+        .opt("-c", "echo $GREETING; printf 'a\\tb' >&2");
+    let (results, _, replayer) = record_cassette("output", &[&ok]);
+
+    let output = replayer.execute(&ok).unwrap();
+    assert_eq!(output.stdout, results[0].as_ref().unwrap().stdout);
+    assert_eq!(output.stdout_lossy(), "hello\n");
+    assert_eq!(output.stderr_lossy(), "a\tb");
+    assert!(replayer.unused().is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn cassette_record_failed() {
+    use crate::executor::Executor;
+    use crate::Cmd;
+
+    let mut failed = Cmd::with_name("sh");
+    failed.current_dir("/").opt("-c", "pwd; exit 3");
+    let (results, _, replayer) = record_cassette("failed", &[&failed]);
+
+    let err = replayer.execute(&failed).unwrap_err();
+    assert_eq!(err.code(), results[0].as_ref().unwrap_err().code());
+    assert_eq!(
+        err.to_string(),
+        "`(cd / && sh -c 'pwd; exit 3')`: exit code 3"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn cassette_record_multi_line() {
+    use crate::executor::Executor;
+    use crate::Cmd;
+
+    // multi-line script and environment variable
+    let mut lines = Cmd::with_name("sh");
+    lines
+        .env("MSG", "it's\nhere")
+        .opt("-c", "echo \"$MSG\"\necho 'done'");
+    let (_, file, replayer) = record_cassette("multi_line", &[&lines]);
+
+    assert!(file.contains(
+        "$ sh -c $'echo \"$MSG\"\\necho \\'done\\''\n\
+         env: MSG=$'it\\'s\\nhere'\n"
+    ));
+    let output = replayer.execute(&lines).unwrap();
+    assert_eq!(output.stdout_lossy(), "it's\nhere\ndone\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn cassette_record_signal() {
+    use crate::executor::Executor;
+    use crate::{Cmd, CmdError};
+    use std::os::unix::process::ExitStatusExt;
+
+    let mut killed = Cmd::with_name("sh");
+    killed.opt("-c", "kill -9 $$");
+    let (results, _, replayer) = record_cassette("signal", &[&killed]);
+
+    assert!(results[0].is_err());
+    match replayer.execute(&killed) {
+        Err(CmdError::Failed { output, .. }) => {
+            assert_eq!(output.code(), None);
+            assert_eq!(output.status.signal(), Some(9));
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn cassette_record_missing_binary() {
+    use crate::Cmd;
+
+    // command not started is not recorded
+    let (results, file, replayer) =
+        record_cassette("missing_binary", &[&Cmd::with_name("/nonexistent")]);
+    assert!(results[0].is_err());
+    assert_eq!(file, "# cmd_builder cassette\n");
+    assert!(replayer.unused().is_empty());
+}
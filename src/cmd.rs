@@ -1,10 +1,22 @@
+use super::arg::{Arg, OptionStyle, FLAGS_ARGS_SEPARATOR};
+use super::exec::{CmdError, CmdOutput};
+use super::parse::{split_posix, ParseError, Word};
+use super::quote::{cmd_exe_escape, windows_quote};
+use super::shell::PosixLine;
+use super::stdio::{apply_stdio, Redirect};
 use super::CmdList;
 use std::borrow::Cow;
 use std::fmt;
-use std::process::Command;
+use std::io::{self, Write};
+use std::process::{Child, Command, ExitStatus};
+use std::str::FromStr;
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
 
 const EMPTY_CMD: &str = "";
 const CMD_ARG_SEPARATOR: &str = " ";
+/// conventional parameter for stdin (or stdout), not a flag despite the hyphen
+const STDIN_PARAM: &str = "-";
 
 // NOTE: compile time or runtime for shortcuts (alias vs name, combined flags vs separate flags)
 //
@@ -17,10 +29,21 @@ const CMD_ARG_SEPARATOR: &str = " ";
 // .to_vec() -> vec![command, arg1, arg2, ... arg3, subcommand1, ..., subcommand2 ...]
 // .to_string() -> "command arg1 arg ... arg3 subcommand1 ... ; subcommand2 ...";
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Cmd<'a> {
     /// environment variables
     pub envs: Option<Vec<(Cow<'a, str>, Cow<'a, str>)>>,
 
+    /// clear inherited environment variables (`env -i`)
+    pub env_clear: bool,
+
+    /// inherited environment variables to remove (`env -u VAR`)
+    pub env_removes: Option<Vec<Cow<'a, str>>>,
+
+    /// working directory (`cd DIR && ...`)
+    pub current_dir: Option<Cow<'a, str>>,
+
     /// command name
     pub name: Option<Cow<'a, str>>,
 
@@ -35,30 +58,47 @@ pub struct Cmd<'a> {
     pub flags_short: Option<String>,
 
     /// arguments: long flags, options, parameters (`[--longflag] [-o opt] [param]`)
-    pub args: Option<Vec<Cow<'a, str>>>,
+    pub args: Option<Vec<Arg<'a>>>,
 
     /// subcommands list
     pub subcommands: Option<CmdList<'a>>,
 
-    // XXX: Cow<'a, str> or &'a str?
     /// separator between command and it's flags, args, subcommand (" ")
-    pub separator: Option<&'a str>,
+    pub separator: Option<Cow<'a, str>>,
 
-    // XXX: Cow<'a, str> or &'a str?
-    /// flags, args separator (usually double dash `--`)
-    pub flags_args_separator: Option<&'a str>,
+    /// flags, args separator (usually double dash `--`), inserted before the first parameter,
+    /// arguments keep their order
+    pub flags_args_separator: Option<Cow<'a, str>>,
+
+    /// insert flags, args separator only if some parameter begins with hyphen (`-rf`)
+    pub auto_flags_args_separator: bool,
+
+    /// default style for options without own style (`-t value`, `--target=value`, `-Ivalue`)
+    pub option_style: OptionStyle,
 
     /// combine multiple single flags into flags line (`-f -a` = `-fa`)
     pub combine_short_flags: bool,
 
     /// use command alias instead of name (`new-session` = `new`)
     pub use_alias: bool,
+
+    /// standard input (`< in.txt`), `None` = default of the execution method
+    pub stdin: Option<Redirect<'a>>,
+
+    /// standard output (`> out.log`), `None` = default of the execution method
+    pub stdout: Option<Redirect<'a>>,
+
+    /// standard error (`2> err.log`, `2>&1`), `None` = default of the execution method
+    pub stderr: Option<Redirect<'a>>,
 }
 
 impl<'a> Default for Cmd<'a> {
     fn default() -> Self {
         Cmd {
             envs: None,
+            env_clear: false,
+            env_removes: None,
+            current_dir: None,
             name: None,
             alias: None,
             flags: None,
@@ -67,22 +107,20 @@ impl<'a> Default for Cmd<'a> {
             subcommands: None,
             separator: None,
             flags_args_separator: None,
+            auto_flags_args_separator: false,
+            option_style: OptionStyle::Separate,
             combine_short_flags: true,
             use_alias: true,
+            stdin: None,
+            stdout: None,
+            stderr: None,
         }
     }
 }
 
-// XXX: reason?
-// s. clap
-//macro_rules! tmux_command!("env", "cmd", "-a", "-b", "-arg 0", "param")
-
 impl<'a> fmt::Display for Cmd<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let output = self
-            .to_vec()
-            .join(self.separator.unwrap_or(CMD_ARG_SEPARATOR));
-        write!(f, "{}", output)
+        write!(f, "{}", self.to_shell_string())
     }
 }
 
@@ -120,6 +158,113 @@ impl<'a> Cmd<'a> {
         }
     }
 
+    /// Convert to `Cmd` owning all strings, so it can outlive the borrowed data (e.g. be stored
+    /// for later inspection)
+    pub fn into_owned(self) -> Cmd<'static> {
+        let owned = |s: Cow<'a, str>| -> Cow<'static, str> { Cow::Owned(s.into_owned()) };
+        Cmd {
+            envs: self.envs.map(|envs| {
+                envs.into_iter()
+                    .map(|(key, value)| (owned(key), owned(value)))
+                    .collect()
+            }),
+            env_clear: self.env_clear,
+            env_removes: self
+                .env_removes
+                .map(|keys| keys.into_iter().map(owned).collect()),
+            current_dir: self.current_dir.map(owned),
+            name: self.name.map(owned),
+            alias: self.alias.map(owned),
+            flags: self
+                .flags
+                .map(|flags| flags.into_iter().map(owned).collect()),
+            flags_short: self.flags_short,
+            args: self
+                .args
+                .map(|args| args.into_iter().map(Arg::into_owned).collect()),
+            subcommands: self.subcommands.map(CmdList::into_owned),
+            separator: self.separator.map(owned),
+            flags_args_separator: self.flags_args_separator.map(owned),
+            auto_flags_args_separator: self.auto_flags_args_separator,
+            option_style: self.option_style,
+            combine_short_flags: self.combine_short_flags,
+            use_alias: self.use_alias,
+            stdin: self.stdin.map(Redirect::into_owned),
+            stdout: self.stdout.map(Redirect::into_owned),
+            stderr: self.stderr.map(Redirect::into_owned),
+        }
+    }
+
+    /// Create `Cmd` from a POSIX sh command line
+    ///
+    /// * leading `KEY=VALUE` words are stored in `Cmd.envs`
+    /// * next word is stored in `Cmd.name`
+    /// * short flags clusters (`-a`, `-abc`) directly following the name are stored in
+    ///   `Cmd.flags_short`
+    /// * all remaining words are stored in `Cmd.args`, words starting with hyphen as
+    ///   [`Arg::Flag`], first `--` as [`Arg::Separator`], all others (including `-`, stdin) as
+    ///   [`Arg::Param`] (option values can not be distinguished from parameters)
+    /// * unquoted shell operators (`;`, `|`, `&`, `<`, `>`, `(`, `)`) are an error
+    ///
+    /// Quoting is removed (see [`Cmd::to_shell_string()`] for the reverse), no expansions are
+    /// performed
+    ///
+    /// # Examples
+    /// ```
+    /// use cmd_builder::Cmd;
+    ///
+    /// let cmd = Cmd::parse_shell("LANGUAGE=en_US.UTF-8 git -ab --long x -- 'my file'").unwrap();
+    /// assert_eq!(cmd.name.as_deref(), Some("git"));
+    /// assert_eq!(cmd.flags_short.as_deref(), Some("ab"));
+    /// assert_eq!(
+    ///     cmd.to_vec(),
+    ///     vec!["LANGUAGE=en_US.UTF-8", "git", "-ab", "--long", "x", "--", "my file"]
+    /// );
+    /// ```
+    pub fn parse_shell(s: &str) -> Result<Self, ParseError> {
+        Ok(Cmd::from_words(split_posix(s)?))
+    }
+
+    // create `Cmd` from already split words (envs, name, short flags, args)
+    pub(crate) fn from_words(words: Vec<Word>) -> Self {
+        let mut cmd = Cmd::new();
+        let mut words = words.into_iter().peekable();
+
+        while let Some(word) = words.next_if(|word| word.assignment().is_some()) {
+            if let Some((key, value)) = word.assignment() {
+                cmd.env(key.to_string(), value.to_string());
+            }
+        }
+
+        if let Some(word) = words.next() {
+            cmd.name(word.value);
+        }
+
+        while let Some(word) = words.next_if(|word| word.short_flags().is_some()) {
+            if let Some(flags) = word.short_flags() {
+                for c in flags.chars() {
+                    cmd.push_flag_short(c);
+                }
+            }
+        }
+
+        let mut separated = false;
+        for word in words {
+            if separated {
+                cmd.push_param(word.value);
+            } else if word.value == FLAGS_ARGS_SEPARATOR {
+                separated = true;
+                cmd.push_arg(Arg::Separator);
+            } else if word.value.starts_with('-') && word.value != STDIN_PARAM {
+                cmd.push_flag(word.value);
+            } else {
+                cmd.push_param(word.value);
+            }
+        }
+
+        cmd
+    }
+
     pub fn with_cmds(cmdlist: CmdList<'a>) -> Self {
         Cmd {
             subcommands: Some(cmdlist),
@@ -151,12 +296,29 @@ impl<'a> Cmd<'a> {
         self
     }
 
+    /// Set `Cmd.env_clear` to `true`, inherited environment variables will not be passed
+    pub fn env_clear(&mut self) -> &mut Self {
+        self.env_clear = true;
+        self
+    }
+
+    /// Add an inherited environment variable to remove to `Cmd.env_removes`
+    pub fn env_remove<S: Into<Cow<'a, str>>>(&mut self, key: S) -> &mut Self {
+        self.env_removes.get_or_insert(Vec::new()).push(key.into());
+        self
+    }
+
+    /// Set `Cmd.current_dir` field
+    pub fn current_dir<S: Into<Cow<'a, str>>>(&mut self, dir: S) -> &mut Self {
+        self.current_dir = Some(dir.into());
+        self
+    }
+
     // XXX: hard bound to cmd_args
     // if vec doesn't exist, creates it and appends with given arguments
     /// push a single flag (`-x`)
     pub fn push_flag<S: Into<Cow<'a, str>>>(&mut self, flag: S) -> &mut Self {
-        self.args.get_or_insert(Vec::new()).push(flag.into());
-        self
+        self.push_arg(Arg::flag(flag))
     }
 
     pub fn push_flag_short(&mut self, flag: char) -> &mut Self {
@@ -171,16 +333,35 @@ impl<'a> Cmd<'a> {
         U: Into<Cow<'a, str>>,
         V: Into<Cow<'a, str>>,
     {
-        self.args
-            .get_or_insert(Vec::new())
-            .extend_from_slice(&[key.into(), option.into()]);
-        self
+        self.push_arg(Arg::option(key, option))
+    }
+
+    // if vec doesn't exist, creates it and appends with given arguments
+    /// push an option with given style (`-x  <VALUE>`, `--x=<VALUE>`, `-x<VALUE>`), overriding
+    /// `Cmd.option_style`
+    pub fn push_option_ext<U, V>(&mut self, key: U, option: V, style: OptionStyle) -> &mut Self
+    where
+        U: Into<Cow<'a, str>>,
+        V: Into<Cow<'a, str>>,
+    {
+        self.push_arg(Arg::option_ext(key, option, style))
     }
 
     // if vec doesn't exist, creates it and appends with given arguments
     /// push a single parameter (`<VALUE>`)
     pub fn push_param<S: Into<Cow<'a, str>>>(&mut self, param: S) -> &mut Self {
-        self.args.get_or_insert(Vec::new()).push(param.into());
+        self.push_arg(Arg::param(param))
+    }
+
+    /// push an argument with unknown role, passed as is
+    pub fn push_raw<S: Into<Cow<'a, str>>>(&mut self, raw: S) -> &mut Self {
+        self.push_arg(Arg::raw(raw))
+    }
+
+    // if vec doesn't exist, creates it and appends with given arguments
+    /// push a typed argument (flag, option, parameter, separator, raw)
+    pub fn push_arg(&mut self, arg: Arg<'a>) -> &mut Self {
+        self.args.get_or_insert(Vec::new()).push(arg);
         self
     }
 
@@ -201,10 +382,7 @@ impl<'a> Cmd<'a> {
         T: Into<Cow<'a, str>>,
         U: Into<Cow<'a, str>>,
     {
-        let v = self.args.get_or_insert(Vec::new());
-        v.push(flag.into());
-        v.push(opt.into());
-        self
+        self.push_arg(Arg::option(flag, opt))
     }
 
     // XXX: -> &mut Self, or Self
@@ -213,14 +391,57 @@ impl<'a> Cmd<'a> {
         T: Into<Cow<'a, str>>,
         U: Into<Cow<'a, str>>,
     {
-        let v = self.args.get_or_insert(Vec::new());
-        v.push(short.into());
-        v.push(opt.into());
-        self
+        self.push_arg(Arg::option(short, opt))
     }
 
     pub fn param<T: Into<Cow<'a, str>>>(&mut self, param: T) -> &mut Self {
-        self.args.get_or_insert(Vec::new()).push(param.into());
+        self.push_arg(Arg::param(param))
+    }
+
+    /// Set `Cmd.flags_args_separator` field
+    pub fn flags_args_separator<S: Into<Cow<'a, str>>>(&mut self, separator: S) -> &mut Self {
+        self.flags_args_separator = Some(separator.into());
+        self
+    }
+
+    /// Set `Cmd.auto_flags_args_separator` to `true`, flags, args separator (`Cmd.flags_args_separator`
+    /// or `--` if not set) will be inserted only if some parameter begins with hyphen
+    pub fn auto_flags_args_separator(&mut self) -> &mut Self {
+        self.auto_flags_args_separator = true;
+        self
+    }
+
+    pub fn not_auto_flags_args_separator(&mut self) -> &mut Self {
+        self.auto_flags_args_separator = false;
+        self
+    }
+
+    pub fn auto_flags_args_separator_ext(&mut self, state: bool) -> &mut Self {
+        self.auto_flags_args_separator = state;
+        self
+    }
+
+    /// Set `Cmd.stdin` field
+    pub fn stdin(&mut self, stdin: Redirect<'a>) -> &mut Self {
+        self.stdin = Some(stdin);
+        self
+    }
+
+    /// Set `Cmd.stdout` field
+    pub fn stdout(&mut self, stdout: Redirect<'a>) -> &mut Self {
+        self.stdout = Some(stdout);
+        self
+    }
+
+    /// Set `Cmd.stderr` field
+    pub fn stderr(&mut self, stderr: Redirect<'a>) -> &mut Self {
+        self.stderr = Some(stderr);
+        self
+    }
+
+    /// Set `Cmd.option_style` field, used for options without own style
+    pub fn option_style(&mut self, style: OptionStyle) -> &mut Self {
+        self.option_style = style;
         self
     }
 
@@ -272,27 +493,132 @@ impl<'a> Cmd<'a> {
     //}
 
     // NOTE: can't be consuming `to_vec(self)`, borrowing used in `fmt(&self)`
-    /// Transform `Cmd` to `Vec<Cow<'a, str>>`
+    /// Transform `Cmd` to `Vec<Cow<'a, str>>` (environment variables as `KEY=VALUE`, followed by
+    /// [`Cmd::to_argv()`])
     pub fn to_vec(&self) -> Vec<Cow<'a, str>> {
         let mut v: Vec<Cow<'a, str>> = Vec::new();
 
+        for (key, value) in self.to_envs() {
+            v.push(Cow::Owned(format!("{}={}", key, value)));
+        }
+
+        v.extend(self.to_argv());
+
+        v
+    }
+
+    /// Transform `Cmd` to a POSIX sh command line, every environment variable value, name, flag,
+    /// option and parameter is quoted if needed (see [`crate::quote::posix_quote()`]), stdin,
+    /// stdout, stderr redirections are appended (in-memory stdin is piped from `printf`, see
+    /// [`Redirect::to_printf_string()`]), environment clearing, removing are prepended
+    /// (`env -i -u VAR ...`), working directory is changed in a subshell (`(cd DIR && ...)`)
+    ///
+    /// # Examples
+    /// ```
+    /// use cmd_builder::{Cmd, Redirect};
+    ///
+    /// let mut cmd = Cmd::with_name("cat");
+    /// cmd.env("LANG", "C").param("my file; rm -rf ~");
+    /// assert_eq!(cmd.to_shell_string(), "LANG=C cat 'my file; rm -rf ~'");
+    ///
+    /// cmd.stdin(Redirect::file("in.txt"))
+    ///     .stdout(Redirect::file("out.log"))
+    ///     .stderr(Redirect::Stdout);
+    /// assert_eq!(
+    ///     cmd.to_shell_string(),
+    ///     "LANG=C cat 'my file; rm -rf ~' < in.txt > out.log 2>&1"
+    /// );
+    /// ```
+    pub fn to_shell_string(&self) -> String {
+        let envs = self.to_envs();
+        let argv = self.to_argv();
+        PosixLine {
+            current_dir: self.current_dir.as_deref(),
+            env_clear: self.env_clear,
+            env_removes: self
+                .env_removes
+                .as_ref()
+                .map(|keys| keys.iter().map(|key| key.as_ref()).collect()),
+            envs: envs
+                .iter()
+                .map(|(key, value)| (key.as_ref(), value.as_ref()))
+                .collect(),
+            argv: argv.iter().map(|arg| arg.as_ref()).collect(),
+            stdin: self.stdin.as_ref(),
+            stdout: self.stdout.as_ref(),
+            stderr: self.stderr.as_ref(),
+            separator: self.separator.as_deref().unwrap_or(CMD_ARG_SEPARATOR),
+        }
+        .render()
+    }
+
+    /// Transform `Cmd` to a Windows command line, program and all arguments are quoted if needed
+    /// (see [`windows_quote()`]), environment variables, working directory and redirections
+    /// are not rendered (they have to be set for the process)
+    ///
+    /// # Examples
+    /// ```
+    /// use cmd_builder::Cmd;
+    ///
+    /// let cmd = Cmd::with_name("robocopy").param(r"C:\my dir\").param("D:\\backup").to_owned();
+    /// assert_eq!(cmd.to_windows_string(), r#"robocopy "C:\my dir\\" D:\backup"#);
+    /// ```
+    pub fn to_windows_string(&self) -> String {
+        self.to_argv()
+            .iter()
+            .map(|arg| windows_quote(arg))
+            .collect::<Vec<_>>()
+            .join(CMD_ARG_SEPARATOR)
+    }
+
+    /// Transform `Cmd` to a command line passed through cmd.exe (`cmd /c ...`), Windows command
+    /// line (see [`Cmd::to_windows_string()`]) with escaped metacharacters (see
+    /// [`cmd_exe_escape()`])
+    ///
+    /// # Examples
+    /// ```
+    /// use cmd_builder::Cmd;
+    ///
+    /// let cmd = Cmd::with_name("echo").param("a & b").param("100%").to_owned();
+    /// assert_eq!(cmd.to_cmd_exe_string(), r#"echo ^"a ^& b^" 100^%"#);
+    /// ```
+    pub fn to_cmd_exe_string(&self) -> String {
+        cmd_exe_escape(&self.to_windows_string()).into_owned()
+    }
+
+    /// Environment variables of the command and all it's subcommands (subcommands are executed
+    /// by the same process)
+    pub fn to_envs(&self) -> Vec<(Cow<'a, str>, Cow<'a, str>)> {
+        let mut v = Vec::new();
+
         if let Some(envs) = &self.envs {
-            for (key, value) in envs {
-                v.push(Cow::Owned(format!("{}={}", key, value)));
+            v.extend(envs.iter().cloned());
+        }
+
+        if let Some(cmds) = &self.subcommands {
+            for cmd in &cmds.commands {
+                v.extend(cmd.to_envs());
             }
         }
 
+        v
+    }
+
+    /// Transform `Cmd` to `Vec<Cow<'a, str>>` without environment variables: program (command
+    /// name or alias), short flags, arguments, subcommands. Used by all outputs: `to_vec()`,
+    /// `Display`, `to_command()`
+    pub fn to_argv(&self) -> Vec<Cow<'a, str>> {
+        let mut v: Vec<Cow<'a, str>> = Vec::new();
+
         // XXX: ugly
         if self.use_alias {
             if let Some(alias) = &self.alias {
-                v.push(alias.to_owned());
+                v.push(alias.clone());
             } else if let Some(name) = &self.name {
-                v.push(name.to_owned());
-            }
-        } else {
-            if let Some(name) = &self.name {
-                v.push(name.to_owned());
+                v.push(name.clone());
             }
+        } else if let Some(name) = &self.name {
+            v.push(name.clone());
         }
 
         if let Some(flags_short) = &self.flags_short {
@@ -305,51 +631,168 @@ impl<'a> Cmd<'a> {
             }
         }
 
-        if let Some(args) = &self.args {
-            v.extend(args.to_vec());
-        }
+        v.extend(self.args_to_vec());
 
         if let Some(cmds) = &self.subcommands {
-            v.extend(cmds.to_vec());
+            v.extend(cmds.to_argv());
         }
 
         v
     }
 
-    /// Transform `Cmd` into [`std::process::Command`]
-    pub fn to_command(self) -> Command {
-        let name = self.name.as_ref().unwrap_or(&Cow::Borrowed(""));
-        let mut command = Command::new(name.as_ref());
-
-        if let Some(envs) = self.envs {
-            command.envs(
-                envs.iter()
-                    .map(|(key, value)| (key.as_ref(), value.as_ref())),
-            );
+    // arguments with flags, args separator inserted before the first parameter (if needed)
+    fn args_to_vec(&self) -> Vec<Cow<'a, str>> {
+        let args = match &self.args {
+            Some(args) => args.as_slice(),
+            None => &[],
+        };
+
+        // position of the inserted separator: before the first parameter beginning with hyphen
+        // (auto) or before the first parameter (always), at the end if there are no parameters
+        let position = if args.contains(&Arg::Separator) {
+            None
+        } else if self.auto_flags_args_separator {
+            args.iter()
+                .position(|arg| matches!(arg, Arg::Param(param) if param.starts_with('-')))
+        } else {
+            self.flags_args_separator
+                .as_ref()
+                .map(|_| args.iter().position(Arg::is_param).unwrap_or(args.len()))
+        };
+
+        // relative order of all arguments is kept
+        let mut v = Vec::new();
+        for (i, arg) in args.iter().enumerate() {
+            if position == Some(i) {
+                v.push(self.flags_args_separator_or_default());
+            }
+            v.extend(arg.to_vec_ext(self.option_style));
         }
-
-        if let Some(args) = self.args {
-            command.args(args.iter().map(|arg| arg.as_ref()));
+        if position == Some(args.len()) {
+            v.push(self.flags_args_separator_or_default());
         }
 
-        // additional subcommands
-        if let Some(cmds) = self.subcommands {
-            command.args(cmds.to_vec().iter().map(|arg| arg.as_ref()));
+        v
+    }
+
+    fn flags_args_separator_or_default(&self) -> Cow<'a, str> {
+        self.flags_args_separator
+            .clone()
+            .unwrap_or(Cow::Borrowed(FLAGS_ARGS_SEPARATOR))
+    }
+
+    /// Transform `Cmd` into [`std::process::Command`], only stdio configurations not requiring
+    /// opening files are applied (see [`Cmd::try_to_command()`])
+    pub fn to_command(self) -> Command {
+        Command::from(&self)
+    }
+
+    /// Transform `Cmd` into [`std::process::Command`], applying all stdio configurations (files
+    /// are opened), in-memory stdin is piped, but not written
+    pub fn try_to_command(&self) -> io::Result<Command> {
+        self.to_command_stdio(false)
+    }
+
+    // `Command` with applied stdio, not configured streams are captured (`capture`, stdin is
+    // null) or inherited
+    pub(crate) fn to_command_stdio(&self, capture: bool) -> io::Result<Command> {
+        let mut command = Command::from(self);
+        apply_stdio(
+            &mut command,
+            self.stdin.as_ref(),
+            self.stdout.as_ref(),
+            self.stderr.as_ref(),
+            capture,
+        )?;
+        Ok(command)
+    }
+
+    // spawn, write in-memory stdin, wait for exit
+    fn execute(&self, capture: bool) -> Result<CmdOutput, CmdError> {
+        let io_error = |error| CmdError::Io {
+            command: self.to_string(),
+            error,
+        };
+
+        let mut command = self.to_command_stdio(capture).map_err(io_error)?;
+        let start = Instant::now();
+        let mut child = command.spawn().map_err(io_error)?;
+        let writer = self.write_stdin(&mut child);
+        let output = child.wait_with_output().map_err(io_error)?;
+        join_stdin(writer).map_err(io_error)?;
+
+        Ok(CmdOutput::new(output, start.elapsed()))
+    }
+
+    // written in a separate thread, process may write stdout before reading stdin
+    pub(crate) fn write_stdin(&self, child: &mut Child) -> Option<JoinHandle<io::Result<()>>> {
+        match (&self.stdin, child.stdin.take()) {
+            (Some(Redirect::Bytes(bytes)), Some(mut stdin)) => {
+                let bytes = bytes.to_vec();
+                Some(thread::spawn(move || stdin.write_all(&bytes)))
+            }
+            _ => None,
         }
+    }
 
-        //if let Some(stdin) = self.stdin {
-        //command.stdin(stdin);
-        //}
+    /// Execute command, capturing stdout and stderr (if not configured otherwise), non-zero exit
+    /// code is not an error
+    ///
+    /// # Examples
+    /// ```
+    /// use cmd_builder::Cmd;
+    ///
+    /// let output = Cmd::with_name("sh").opt("-c", "exit 3").output().unwrap();
+    /// assert_eq!(output.code(), Some(3));
+    /// ```
+    pub fn output(&self) -> Result<CmdOutput, CmdError> {
+        self.execute(true)
+    }
 
-        //if let Some(stdout) = self.stdout {
-        //command.stdout(stdout);
-        //}
+    /// Execute command, capturing stdout and stderr (if not configured otherwise), non-zero exit
+    /// code is an error ([`CmdError::Failed`] containing the captured output)
+    ///
+    /// # Examples
+    /// ```
+    /// use cmd_builder::Cmd;
+    ///
+    /// let err = Cmd::with_name("sh").opt("-c", "echo oops >&2; exit 3").run().unwrap_err();
+    /// assert_eq!(err.code(), Some(3));
+    /// assert_eq!(err.to_string(), "`sh -c 'echo oops >&2; exit 3'`: exit code 3: oops");
+    /// ```
+    pub fn run(&self) -> Result<CmdOutput, CmdError> {
+        let output = self.output()?;
+        if output.success() {
+            Ok(output)
+        } else {
+            Err(CmdError::Failed {
+                command: self.to_string(),
+                output,
+            })
+        }
+    }
 
-        //if let Some(stderr) = self.stderr {
-        //command.stderr(stderr);
-        //}
+    /// Execute command with inherited stdin, stdout and stderr (if not configured otherwise),
+    /// non-zero exit code is not an error
+    pub fn status(&self) -> Result<ExitStatus, CmdError> {
+        self.execute(false).map(|output| output.status)
+    }
 
-        command
+    /// Execute command (see [`Cmd::run()`]) and return it's stdout as string
+    ///
+    /// # Examples
+    /// ```
+    /// use cmd_builder::Cmd;
+    ///
+    /// let s = Cmd::with_name("echo").param("hello").output_string().unwrap();
+    /// assert_eq!(s, "hello\n");
+    /// ```
+    pub fn output_string(&self) -> Result<String, CmdError> {
+        let output = self.run()?;
+        String::from_utf8(output.stdout).map_err(|error| CmdError::Utf8 {
+            command: self.to_string(),
+            error,
+        })
     }
 
     //pub fn into_tmux_command(self) -> TmuxCommand<'a> {
@@ -397,6 +840,22 @@ impl<'a> Cmd<'a> {
     //}
 }
 
+impl<'a> FromStr for Cmd<'a> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Cmd::parse_shell(s)
+    }
+}
+
+// wait for in-memory stdin writer, process may exit without reading all input
+pub(crate) fn join_stdin(writer: Option<JoinHandle<io::Result<()>>>) -> io::Result<()> {
+    match writer.map(|writer| writer.join()) {
+        Some(Ok(Err(error))) if error.kind() != io::ErrorKind::BrokenPipe => Err(error),
+        _ => Ok(()),
+    }
+}
+
 // create ready to exec [`std::process::Command`]
 // * create [`std::process::Command`]
 // * push environment variables
@@ -404,26 +863,47 @@ impl<'a> Cmd<'a> {
 // * push subcommand
 impl<'a> From<&Cmd<'a>> for Command {
     fn from(cmd: &Cmd) -> Self {
+        let argv = cmd.to_argv();
+
         // user given command or blank command
-        let name = cmd.name.as_ref().unwrap_or(&Cow::Borrowed(EMPTY_CMD));
-        let mut command = Command::new(name.as_ref());
-
-        // environment variables
-        if let Some(envs) = &cmd.envs {
-            command.envs(
-                envs.iter()
-                    .map(|(key, value)| (key.as_ref(), value.as_ref())),
-            );
+        let (name, args) = match argv.split_first() {
+            Some((name, args)) => (name.as_ref(), args),
+            None => (EMPTY_CMD, &[] as &[Cow<str>]),
+        };
+        let mut command = Command::new(name);
+
+        // working directory
+        if let Some(dir) = &cmd.current_dir {
+            command.current_dir(dir.as_ref());
         }
 
-        // arguments
-        if let Some(args) = &cmd.args {
-            command.args(args.iter().map(|arg| arg.as_ref()));
+        // inherited environment variables
+        if cmd.env_clear {
+            command.env_clear();
         }
+        for key in cmd.env_removes.iter().flatten() {
+            command.env_remove(key.as_ref());
+        }
+
+        // environment variables (including subcommands)
+        command.envs(
+            cmd.to_envs()
+                .iter()
+                .map(|(key, value)| (key.as_ref(), value.as_ref())),
+        );
+
+        // arguments and subcommands
+        command.args(args.iter().map(|arg| arg.as_ref()));
 
-        // subcommands
-        if let Some(cmds) = &cmd.subcommands {
-            command.args(cmds.to_vec().iter().map(|arg| arg.as_ref()));
+        // stdio (not requiring opening files)
+        if let Some(stdin) = cmd.stdin.as_ref().and_then(|stdin| stdin.to_stdio()) {
+            command.stdin(stdin);
+        }
+        if let Some(stdout) = cmd.stdout.as_ref().and_then(|stdout| stdout.to_stdio()) {
+            command.stdout(stdout);
+        }
+        if let Some(stderr) = cmd.stderr.as_ref().and_then(|stderr| stderr.to_stdio()) {
+            command.stderr(stderr);
         }
 
         command
@@ -1,44 +1,169 @@
+use super::exec::{CmdError, CmdListOutput};
+use super::parse::{split_tmux, ParseError};
+use super::pipeline::Pipeline;
+use super::shell::Shell;
 use super::Cmd;
 use std::borrow::Cow;
 use std::fmt;
 use std::process::Command;
 
-/// command separator [^f1]
+/// Commands sequence execution semantics
+///
+/// ```text
+/// Always    - execute all commands (`a ; b ; c`)
+/// OnSuccess - execute next command only if the previous one succeeded (`a && b && c`)
+/// OnFailure - execute next command only if the previous one failed (`a || b || c`)
+/// Pipe      - execute all commands simultaneously connected by pipes (`a | b | c`)
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum RunPolicy {
+    #[default]
+    Always,
+    OnSuccess,
+    OnFailure,
+    Pipe,
+}
+
+/// Separator between commands of `CmdList`
+///
+/// ```text
+/// Semicolon        - `;`, shell command sequence or tmux command sequence passed as argv
+/// EscapedSemicolon - `\;`, tmux command sequence inside a single shell word or config file
+/// Newline          - `\n`, one command per line (script)
+/// And              - `&&`, run the next command only if the previous one succeeded
+/// Or               - `||`, run the next command only if the previous one failed
+/// Pipe             - `|`, stdout of each command is stdin of the next one
+/// Custom           - any other separator, passed as is (executed as `Semicolon`)
+/// ```
+///
+/// tmux [^f1]
 ///
 /// [^f1] "...Each command is terminated by a newline or a semicolon (;) Commands separated by
 /// semicolons together form a ‘command sequence’ - if a command in the sequence encounters an
 /// error, no subsequent commands are executed..."
 /// [[tmux manual](https://man7.org/linux/man-pages/man1/tmux.1.html#COMMAND_PARSING_AND_EXECUTION)]
+#[derive(Debug, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Separator<'a> {
+    #[default]
+    Semicolon,
+    EscapedSemicolon,
+    Newline,
+    And,
+    Or,
+    Pipe,
+    Custom(Cow<'a, str>),
+}
+
+impl<'a> Separator<'a> {
+    /// Convert to `Separator` owning the custom string
+    pub fn into_owned(self) -> Separator<'static> {
+        match self {
+            Separator::Semicolon => Separator::Semicolon,
+            Separator::EscapedSemicolon => Separator::EscapedSemicolon,
+            Separator::Newline => Separator::Newline,
+            Separator::And => Separator::And,
+            Separator::Or => Separator::Or,
+            Separator::Pipe => Separator::Pipe,
+            Separator::Custom(separator) => Separator::Custom(Cow::Owned(separator.into_owned())),
+        }
+    }
 
-// TODO: rename TERMINATOR
-//const CMDS_SEPARATOR: &str = "\\;";
-//const CMDS_SEPARATOR: &str = "\n";
-const CMDS_SEPARATOR: &str = ";";
-const CMD_SEPARATOR: &str = " ";
+    /// Separator as a single argv element
+    ///
+    /// # Examples
+    /// ```
+    /// use cmd_builder::Separator;
+    ///
+    /// assert_eq!(Separator::EscapedSemicolon.as_str(), "\\;");
+    /// assert_eq!(Separator::from("&&"), Separator::And);
+    /// ```
+    pub fn as_str(&self) -> &str {
+        match self {
+            Separator::Semicolon => ";",
+            Separator::EscapedSemicolon => "\\;",
+            Separator::Newline => "\n",
+            Separator::And => "&&",
+            Separator::Or => "||",
+            Separator::Pipe => "|",
+            Separator::Custom(separator) => separator,
+        }
+    }
+
+    /// Execution semantics of the separator (see [`CmdList::run()`])
+    pub fn run_policy(&self) -> RunPolicy {
+        match self {
+            Separator::And => RunPolicy::OnSuccess,
+            Separator::Or => RunPolicy::OnFailure,
+            Separator::Pipe => RunPolicy::Pipe,
+            _ => RunPolicy::Always,
+        }
+    }
+
+    // separator as a `Vec` element
+    fn to_cow(&self) -> Cow<'a, str> {
+        match self {
+            Separator::Custom(separator) => separator.clone(),
+            separator => Cow::Owned(separator.as_str().to_owned()),
+        }
+    }
+}
+
+impl<'a> fmt::Display for Separator<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+// known separators are typed, all others are `Separator::Custom`
+impl<'a> From<Cow<'a, str>> for Separator<'a> {
+    fn from(separator: Cow<'a, str>) -> Self {
+        match separator.as_ref() {
+            ";" => Separator::Semicolon,
+            "\\;" => Separator::EscapedSemicolon,
+            "\n" => Separator::Newline,
+            "&&" => Separator::And,
+            "||" => Separator::Or,
+            "|" => Separator::Pipe,
+            _ => Separator::Custom(separator),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Separator<'a> {
+    fn from(separator: &'a str) -> Self {
+        Separator::from(Cow::Borrowed(separator))
+    }
+}
+
+impl<'a> From<String> for Separator<'a> {
+    fn from(separator: String) -> Self {
+        Separator::from(Cow::<str>::Owned(separator))
+    }
+}
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct CmdList<'a> {
     pub commands: Vec<Cmd<'a>>,
 
-    // XXX: Cow<'a, str> or &'a str?
-    pub separator: Option<Cow<'a, str>>,
+    /// separator between commands, `None` = commands are joined by space
+    pub separator: Option<Separator<'a>>,
 }
 
 impl<'a> fmt::Display for CmdList<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        //let separator = self.separator.as_ref().unwrap_or(&Cow::Borrowed(" "));
-        //let output = self.to_vec().join(separator.as_ref());
-        let output = self.to_vec().join(CMD_SEPARATOR);
-        write!(f, "{}", output)
+        write!(f, "{}", self.to_shell_string())
     }
 }
 
-// None = "", Some = ";", Some = "\n"
 impl<'a> Default for CmdList<'a> {
     fn default() -> Self {
         Self {
             commands: Vec::new(),
-            separator: Some(Cow::Borrowed(CMDS_SEPARATOR)),
+            separator: Some(Separator::Semicolon),
         }
     }
 }
@@ -48,6 +173,46 @@ impl<'a> CmdList<'a> {
         Self::default()
     }
 
+    /// Create `CmdList` from a tmux command sequence (configuration file syntax), one `Cmd` per
+    /// command (see [`Cmd::parse_shell()`] for the `Cmd` fields assignment), environment
+    /// variables are taken from the current process
+    ///
+    /// # Examples
+    /// ```
+    /// use cmd_builder::CmdList;
+    ///
+    /// let cmds = CmdList::parse_tmux("new -d -s 'my session' ; bind x { kill-server } # comment")
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     cmds.to_vec(),
+    ///     vec!["new", "-ds", "my session", ";", "bind", "x", "kill-server"]
+    /// );
+    /// ```
+    pub fn parse_tmux(s: &str) -> Result<Self, ParseError> {
+        CmdList::parse_tmux_with_env(s, |key| std::env::var(key).ok())
+    }
+
+    /// Create `CmdList` from a tmux command sequence, environment variables (`$VAR`, `${VAR}`,
+    /// `~`) are resolved using given `lookup` function
+    pub fn parse_tmux_with_env<F>(s: &str, lookup: F) -> Result<Self, ParseError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let mut cmds = CmdList::new();
+        for words in split_tmux(s, lookup)? {
+            cmds.push(Cmd::from_words(words));
+        }
+        Ok(cmds)
+    }
+
+    /// Convert to `CmdList` owning all strings (see [`Cmd::into_owned()`])
+    pub fn into_owned(self) -> CmdList<'static> {
+        CmdList {
+            commands: self.commands.into_iter().map(Cmd::into_owned).collect(),
+            separator: self.separator.map(Separator::into_owned),
+        }
+    }
+
     // XXX: -> Self?
     pub fn push(&mut self, command: Cmd<'a>) {
         self.commands.push(command);
@@ -69,8 +234,7 @@ impl<'a> CmdList<'a> {
 
             if let Some(separator) = &self.separator {
                 if i < len - 1 {
-                    v.push(separator.clone());
-                    //v.push(Cow::Borrowed(separator));
+                    v.push(separator.to_cow());
                 }
             }
         }
@@ -78,6 +242,40 @@ impl<'a> CmdList<'a> {
         v
     }
 
+    // commands without environment variables, used as subcommands of `Cmd`
+    pub(crate) fn to_argv(&self) -> Vec<Cow<'a, str>> {
+        let mut v = Vec::new();
+
+        let len = self.commands.len();
+        for (i, command) in self.commands.iter().enumerate() {
+            v.extend(command.to_argv());
+
+            if let Some(separator) = &self.separator {
+                if i < len - 1 {
+                    v.push(separator.to_cow());
+                }
+            }
+        }
+
+        v
+    }
+
+    /// Transform `CmdList` to a POSIX sh command line, arguments of each command are quoted if
+    /// needed (see [`Cmd::to_shell_string()`]), separators are left unquoted
+    ///
+    /// # Examples
+    /// ```
+    /// use cmd_builder::{Cmd, CmdList};
+    ///
+    /// let cmds = CmdList::new()
+    ///     .cmd(Cmd::with_name("touch").param("a b").to_owned())
+    ///     .cmd(Cmd::with_name("ls").to_owned());
+    /// assert_eq!(cmds.to_shell_string(), "touch 'a b' ; ls");
+    /// ```
+    pub fn to_shell_string(&self) -> String {
+        self.render(Shell::Posix)
+    }
+
     pub fn to_command_vec(self) -> Vec<Command> {
         let mut v = Vec::new();
         for cmd in self.commands {
@@ -86,12 +284,91 @@ impl<'a> CmdList<'a> {
         v
     }
 
-    pub fn separator<S: Into<Cow<'a, str>>>(&mut self, separator: S) -> &mut Self {
+    /// Execute commands using semantics of the separator (see [`Separator::run_policy()`])
+    ///
+    /// # Examples
+    /// ```
+    /// use cmd_builder::{Cmd, CmdList, Separator};
+    ///
+    /// let mut cmds = CmdList::new()
+    ///     .cmd(Cmd::with_name("false").to_owned())
+    ///     .cmd(Cmd::with_name("echo").param("skipped").to_owned());
+    /// cmds.separator(Separator::And);
+    ///
+    /// let output = cmds.run();
+    /// assert!(!output.success());
+    /// assert!(output.outputs[1].is_none());
+    /// ```
+    pub fn run(&self) -> CmdListOutput {
+        let policy = self
+            .separator
+            .as_ref()
+            .map(Separator::run_policy)
+            .unwrap_or_default();
+        self.run_ext(policy)
+    }
+
+    /// Execute commands one after another using given semantics, output of each command is
+    /// captured (see [`Cmd::output()`]), command which could not be executed is failed
+    ///
+    /// [`RunPolicy::Pipe`] executes commands as [`Pipeline`], if the pipeline could not be
+    /// started the error is reported for the first command and all others are skipped
+    pub fn run_ext(&self, policy: RunPolicy) -> CmdListOutput {
+        // whether to execute the next command, given success of the previous one
+        let execute_after: fn(bool) -> bool = match policy {
+            RunPolicy::Always => |_| true,
+            RunPolicy::OnSuccess => |success| success,
+            RunPolicy::OnFailure => |success| !success,
+            RunPolicy::Pipe => return self.run_pipeline(),
+        };
+
+        let mut outputs: Vec<Option<Result<_, CmdError>>> = Vec::new();
+        let mut success = None;
+
+        for cmd in &self.commands {
+            if success.is_none_or(execute_after) {
+                let output = cmd.output();
+                success = Some(output.as_ref().is_ok_and(|output| output.success()));
+                outputs.push(Some(output));
+            } else {
+                outputs.push(None);
+            }
+        }
+
+        CmdListOutput { outputs }
+    }
+
+    // execute all commands as a pipeline
+    fn run_pipeline(&self) -> CmdListOutput {
+        let pipeline = Pipeline {
+            commands: self.commands.clone(),
+            pipefail: false,
+        };
+
+        let outputs = match pipeline.output_stages() {
+            Ok(output) => output.outputs.into_iter().map(|o| Some(Ok(o))).collect(),
+            // error is attached to the command which caused it
+            Err((i, error)) => {
+                let mut outputs: Vec<_> = (0..self.commands.len().max(1)).map(|_| None).collect();
+                outputs[i] = Some(Err(CmdError::Io {
+                    command: self.commands.get(i).map(Cmd::to_string).unwrap_or_default(),
+                    error,
+                }));
+                outputs
+            }
+        };
+
+        CmdListOutput { outputs }
+    }
+
+    /// Set separator, strings of known separators are converted to typed ones (`"&&"` =
+    /// [`Separator::And`])
+    pub fn separator<S: Into<Separator<'a>>>(&mut self, separator: S) -> &mut Self {
         self.separator = Some(separator.into());
         self
     }
 
-    pub fn get_separator(&self) -> Option<&Cow<'a, str>> {
+    pub fn get_separator(&self) -> Option<&Separator<'a>> {
         self.separator.as_ref()
     }
 
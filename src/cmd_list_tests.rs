@@ -43,3 +43,173 @@ fn cmds_list() {
         "ENVVAR=EN new-session -n session_name ; has-session -t session_name ; kill-session -t session_name"
     );
 }
+
+// `echo a`, `false`, not existing command, `echo b`
+#[cfg(unix)]
+fn cmds_list_failing() -> crate::CmdList<'static> {
+    use crate::{Cmd, CmdList};
+
+    CmdList::new()
+        .cmd(Cmd::with_name("echo").param("a").to_owned())
+        .cmd(Cmd::with_name("false").to_owned())
+        .cmd(Cmd::with_name("cmd-builder-missing-binary").to_owned())
+        .cmd(Cmd::with_name("echo").param("b").to_owned())
+}
+
+// `;`
+#[cfg(unix)]
+#[test]
+fn cmds_list_run_always() {
+    let output = cmds_list_failing().run();
+    assert_eq!(output.executed(), 4);
+    assert!(output.success());
+    assert!(output.outputs[2].as_ref().unwrap().is_err());
+}
+
+// `&&`
+#[cfg(unix)]
+#[test]
+fn cmds_list_run_on_success() {
+    use crate::RunPolicy;
+
+    let output = cmds_list_failing().run_ext(RunPolicy::OnSuccess);
+    assert_eq!(output.executed(), 2);
+    assert!(!output.success());
+    let first = output.outputs[0].as_ref().unwrap().as_ref().unwrap();
+    assert_eq!(first.stdout_lossy(), "a\n");
+}
+
+// `||`
+#[cfg(unix)]
+#[test]
+fn cmds_list_run_on_failure() {
+    use crate::{Cmd, CmdList, Separator};
+
+    let mut cmds = CmdList::new()
+        .cmd(Cmd::with_name("false").to_owned())
+        .cmd(Cmd::with_name("true").to_owned())
+        .cmd(Cmd::with_name("echo").to_owned());
+    cmds.separator(Separator::Or);
+    let output = cmds.run();
+    assert_eq!(
+        output
+            .outputs
+            .iter()
+            .map(|output| output.is_some())
+            .collect::<Vec<_>>(),
+        vec![true, true, false]
+    );
+    assert!(output.success());
+}
+
+// `new-session 'a b'` and `kill-server`
+fn cmds_list_two() -> crate::CmdList<'static> {
+    use crate::{Cmd, CmdList};
+
+    CmdList::new()
+        .cmd(Cmd::with_name("new-session").param("a b").to_owned())
+        .cmd(Cmd::with_name("kill-server").to_owned())
+}
+
+#[test]
+fn cmds_list_separator_default() {
+    use crate::Separator;
+
+    let cmds = cmds_list_two();
+    assert_eq!(cmds.get_separator(), Some(&Separator::Semicolon));
+    assert_eq!(cmds.to_string(), "new-session 'a b' ; kill-server");
+}
+
+#[test]
+fn cmds_list_separator_escaped_semicolon() {
+    use crate::Separator;
+
+    let mut cmds = cmds_list_two();
+    cmds.separator(Separator::EscapedSemicolon);
+    assert_eq!(
+        cmds.to_vec(),
+        vec!["new-session", "a b", "\\;", "kill-server"]
+    );
+    assert_eq!(cmds.to_string(), "new-session 'a b' \\; kill-server");
+}
+
+#[test]
+fn cmds_list_separator_newline() {
+    use crate::Separator;
+
+    let mut cmds = cmds_list_two();
+    cmds.separator(Separator::Newline);
+    assert_eq!(
+        cmds.to_vec(),
+        vec!["new-session", "a b", "\n", "kill-server"]
+    );
+    assert_eq!(cmds.to_string(), "new-session 'a b'\nkill-server");
+}
+
+#[test]
+fn cmds_list_separator_from_str() {
+    use crate::Separator;
+
+    // known separators are converted to typed ones
+    let mut cmds = cmds_list_two();
+    cmds.separator("&&");
+    assert_eq!(cmds.get_separator(), Some(&Separator::And));
+    assert_eq!(cmds.to_string(), "new-session 'a b' && kill-server");
+}
+
+#[test]
+fn cmds_list_separator_custom() {
+    use crate::Separator;
+
+    let mut cmds = cmds_list_two();
+    cmds.separator(Separator::Custom("&".into()));
+    assert_eq!(cmds.to_string(), "new-session 'a b' & kill-server");
+}
+
+#[test]
+fn cmds_list_separator_none() {
+    let mut cmds = cmds_list_two();
+    cmds.separator = None;
+    assert_eq!(cmds.to_vec(), vec!["new-session", "a b", "kill-server"]);
+    assert_eq!(cmds.to_string(), "new-session 'a b' kill-server");
+}
+
+#[cfg(unix)]
+#[test]
+fn cmds_list_run_pipe() {
+    use crate::{Cmd, CmdList, Separator};
+
+    let mut cmds = CmdList::new()
+        .cmd(Cmd::with_name("printf").param("b\na\n").to_owned())
+        .cmd(Cmd::with_name("sort").to_owned());
+    cmds.separator(Separator::Pipe);
+    assert_eq!(cmds.to_string(), "printf 'b\na\n' | sort");
+
+    let output = cmds.run();
+    assert_eq!(output.executed(), 2);
+    assert!(output.success());
+    let last = output.outputs[1].as_ref().unwrap().as_ref().unwrap();
+    assert_eq!(last.stdout_lossy(), "a\nb\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn cmds_list_run_pipe_spawn_error() {
+    use crate::{Cmd, CmdError, CmdList, Separator};
+
+    let mut cmds = CmdList::new()
+        .cmd(Cmd::with_name("printf").param("a\n").to_owned())
+        .cmd(Cmd::with_name("cmd_builder_missing_command").to_owned());
+    cmds.separator(Separator::Pipe);
+
+    // error is attached to the command which could not be started
+    let output = cmds.run();
+    assert!(!output.success());
+    assert!(output.outputs[0].is_none());
+    match &output.outputs[1] {
+        Some(Err(CmdError::Io { command, .. })) => {
+            assert_eq!(command, "cmd_builder_missing_command")
+        }
+        other => panic!("unexpected output {:?}", other),
+    }
+}
@@ -26,3 +26,150 @@ fn tmux_test() {
     let s = cmd.to_string();
     assert_eq!(s, "LANGUAGE=en_US.UTF-8 tmux list-commands");
 }
+
+// `new` alias with short flags, option and subcommand with own environment variable
+fn aliased_cmd() -> crate::Cmd<'static> {
+    use crate::cmd::Cmd;
+
+    let mut cmd = Cmd::with_name("new-session");
+    cmd.alias("new")
+        .env("LANGUAGE", "en_US.UTF-8")
+        .push_flag_short('d')
+        .push_flag_short('P')
+        .opt("-s", "my session")
+        .push_cmd(
+            Cmd::with_name("kill-session")
+                .env("TMUX_TMPDIR", "/tmp")
+                .to_owned(),
+        );
+    cmd
+}
+
+#[test]
+fn aliased_cmd_to_vec() {
+    let cmd = aliased_cmd();
+    assert_eq!(
+        cmd.to_vec(),
+        vec![
+            "LANGUAGE=en_US.UTF-8",
+            "TMUX_TMPDIR=/tmp",
+            "new",
+            "-dP",
+            "-s",
+            "my session",
+            "kill-session"
+        ]
+    );
+    assert_eq!(
+        cmd.to_string(),
+        "LANGUAGE=en_US.UTF-8 TMUX_TMPDIR=/tmp new -dP -s 'my session' kill-session"
+    );
+}
+
+#[test]
+fn command_matches_argv() {
+    use std::ffi::OsStr;
+
+    let cmd = aliased_cmd();
+    let argv = cmd.to_argv();
+    let command = cmd.clone().to_command();
+    assert_eq!(command.get_program(), argv[0].as_ref());
+    assert_eq!(
+        command.get_args().collect::<Vec<_>>(),
+        argv[1..]
+            .iter()
+            .map(|arg| OsStr::new(arg.as_ref()))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn command_envs() {
+    use std::ffi::OsStr;
+
+    // environment variables of subcommands are set for the process
+    let command = aliased_cmd().to_command();
+    assert_eq!(
+        command.get_envs().collect::<Vec<_>>(),
+        vec![
+            (OsStr::new("LANGUAGE"), Some(OsStr::new("en_US.UTF-8"))),
+            (OsStr::new("TMUX_TMPDIR"), Some(OsStr::new("/tmp")))
+        ]
+    );
+}
+
+#[test]
+fn command_from_ref() {
+    let cmd = aliased_cmd();
+    let command = std::process::Command::from(&cmd);
+    assert_eq!(command.get_program(), "new");
+    assert_eq!(command.get_args().count(), cmd.to_argv().len() - 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn env_clear_remove_current_dir() {
+    use crate::cmd::Cmd;
+
+    let mut cmd = Cmd::with_name("sh");
+    cmd.current_dir("/")
+        .env_clear()
+        .env_remove("HOME")
+        .env("A", "x y")
+        .opt("-c", "pwd; echo ${HOME:-none} $A");
+    assert_eq!(
+        cmd.to_string(),
+        "(cd / && env -i -u HOME A='x y' sh -c 'pwd; echo ${HOME:-none} $A')"
+    );
+    assert_eq!(cmd.output_string().unwrap(), "/\nnone x y\n");
+}
+
+#[test]
+fn current_dir_command() {
+    use crate::cmd::Cmd;
+
+    let cmd = Cmd::with_name("pwd").current_dir("/").to_owned();
+    let command = std::process::Command::from(&cmd);
+    assert_eq!(command.get_current_dir(), Some(std::path::Path::new("/")));
+}
+
+#[cfg(unix)]
+#[test]
+fn env_remove() {
+    use crate::cmd::Cmd;
+
+    let mut cmd = Cmd::with_name("env");
+    cmd.env_remove("PATH");
+    assert_eq!(cmd.to_string(), "env -u PATH env");
+    let output = cmd.output_string().unwrap();
+    assert!(!output.lines().any(|line| line.starts_with("PATH=")));
+}
+
+#[test]
+fn current_dir_scoped_in_list() {
+    use crate::cmd::Cmd;
+
+    // working directory does not leak into other commands of the line
+    let mut cmds = crate::CmdList::new()
+        .cmd(Cmd::with_name("true"))
+        .cmd(Cmd::with_name("pwd").current_dir("/tmp").to_owned())
+        .cmd(Cmd::with_name("pwd"));
+    cmds.separator(crate::Separator::Or);
+    assert_eq!(cmds.to_string(), "true || (cd /tmp && pwd) || pwd");
+}
+
+#[cfg(unix)]
+#[test]
+fn current_dir_scoped_in_list_run() {
+    use crate::cmd::Cmd;
+
+    let cmds = crate::CmdList::new()
+        .cmd(Cmd::with_name("pwd").current_dir("/tmp").to_owned())
+        .cmd(Cmd::with_name("pwd"));
+    let output = Cmd::with_name("sh")
+        .opt("-c", cmds.to_string())
+        .current_dir("/")
+        .output_string()
+        .unwrap();
+    assert_eq!(output, "/tmp\n/\n");
+}
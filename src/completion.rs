@@ -0,0 +1,470 @@
+//! Shell completion scripts (bash, zsh, fish) generated from [`CmdSpec`]
+//!
+//! Flags, options (values of [`ValueType::Path`] are completed as files, of
+//! [`ValueType::Choice`] as the given words) and subcommands with their aliases are completed.
+//! Parameters are completed only for commands without subcommands.
+//!
+//! # Examples
+//! ```
+//! use cmd_builder::{CmdSpec, FlagSpec};
+//!
+//! let spec = CmdSpec::new("tmux")
+//!     .flag(FlagSpec::short('u').help("UTF-8"))
+//!     .subcommand(CmdSpec::new("new-session").alias("new"));
+//!
+//! let script = spec.to_bash_completion();
+//! assert!(script.contains("complete -F _tmux tmux"));
+//! ```
+use super::quote::{fish_quote, posix_quote};
+use super::spec::{CmdSpec, FlagSpec, ParamSpec, ValueType};
+
+/// generated scripts header
+const GENERATED_BY: &str = "generated by cmd_builder";
+
+// all specifications with the path from the root (`[git, log]`), depth first
+fn walk<'s, 'a>(spec: &'s CmdSpec<'a>) -> Vec<Vec<&'s CmdSpec<'a>>> {
+    let mut v = Vec::new();
+    let mut stack = vec![vec![spec]];
+    while let Some(path) = stack.pop() {
+        let last = path[path.len() - 1];
+        for sub in last.subcommands.iter().rev() {
+            let mut sub_path = path.clone();
+            sub_path.push(sub);
+            stack.push(sub_path);
+        }
+        v.push(path);
+    }
+    v
+}
+
+// function name part (`new-session` = `new_session`)
+fn ident(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+// flag names as written (`-v`, `--verbose`)
+fn flag_names(flag: &FlagSpec) -> Vec<String> {
+    let mut v = Vec::new();
+    if let Some(short) = flag.short {
+        v.push(format!("-{}", short));
+    }
+    if let Some(long) = &flag.long {
+        v.push(format!("--{}", long));
+    }
+    v
+}
+
+// parameters completed for the specification (see module documentation)
+fn completed_params<'s, 'a>(spec: &'s CmdSpec<'a>) -> &'s [ParamSpec<'a>] {
+    if spec.subcommands.is_empty() {
+        &spec.params
+    } else {
+        &[]
+    }
+}
+
+impl<'a> CmdSpec<'a> {
+    /// Generate bash completion script (`complete -F _name name`)
+    pub fn to_bash_completion(&self) -> String {
+        let func = format!("_{}", ident(&self.name));
+        let mut s = String::new();
+
+        s.push_str(&format!(
+            "# bash completion for {}, {}\n",
+            self.name, GENERATED_BY
+        ));
+        s.push_str(&format!("{}() {{\n", func));
+        s.push_str("    local cur=\"${COMP_WORDS[COMP_CWORD]}\"\n");
+        s.push_str("    local prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n");
+        s.push_str(&format!("    local path={} i\n", posix_quote(&self.name)));
+        s.push_str("    COMPREPLY=()\n\n");
+
+        // subcommands path of the current word
+        let paths = walk(self);
+        s.push_str("    for ((i = 1; i < COMP_CWORD; i++)); do\n");
+        s.push_str("        case \"${path} ${COMP_WORDS[i]}\" in\n");
+        for path in &paths {
+            let name = bash_path(path);
+            for sub in &path[path.len() - 1].subcommands {
+                let patterns: Vec<String> = sub
+                    .names()
+                    .map(|alias| posix_quote(&format!("{} {}", name, alias)).into_owned())
+                    .collect();
+                s.push_str(&format!(
+                    "            {}) path={} ;;\n",
+                    patterns.join("|"),
+                    posix_quote(&format!("{} {}", name, sub.name))
+                ));
+            }
+        }
+        s.push_str("        esac\n");
+        s.push_str("    done\n\n");
+
+        s.push_str("    case \"${path}\" in\n");
+        for path in &paths {
+            let spec = path[path.len() - 1];
+            s.push_str(&format!("        {})\n", posix_quote(&bash_path(path))));
+
+            let options: Vec<&FlagSpec> = spec
+                .flags
+                .iter()
+                .filter(|flag| flag.value.is_some())
+                .collect();
+            if !options.is_empty() {
+                s.push_str("            case \"${prev}\" in\n");
+                for flag in options {
+                    let reply = flag.value.as_ref().and_then(bash_compgen);
+                    let reply = match reply {
+                        Some(compgen) => format!("COMPREPLY=($({})); return", compgen),
+                        None => "return".to_string(),
+                    };
+                    s.push_str(&format!(
+                        "                {}) {} ;;\n",
+                        flag_names(flag).join("|"),
+                        reply
+                    ));
+                }
+                s.push_str("            esac\n");
+            }
+
+            let mut words: Vec<String> = spec.flags.iter().flat_map(flag_names).collect();
+            words.extend(
+                spec.subcommands
+                    .iter()
+                    .flat_map(|sub| sub.names().map(str::to_string)),
+            );
+            for param in completed_params(spec) {
+                if let ValueType::Choice(choices) = &param.value {
+                    words.extend(choices.iter().map(|choice| choice.to_string()));
+                }
+            }
+            s.push_str(&format!(
+                "            COMPREPLY=($(compgen -W {} -- \"${{cur}}\"))\n",
+                posix_quote(&words.join(" "))
+            ));
+            if completed_params(spec)
+                .iter()
+                .any(|param| param.value == ValueType::Path)
+            {
+                s.push_str("            COMPREPLY+=($(compgen -f -- \"${cur}\"))\n");
+            }
+            s.push_str("            ;;\n");
+        }
+        s.push_str("    esac\n");
+        s.push_str("}\n");
+        s.push_str(&format!(
+            "complete -F {} {}\n",
+            func,
+            posix_quote(&self.name)
+        ));
+
+        s
+    }
+
+    /// Generate zsh completion script (`#compdef name`, to be placed in `$fpath` as `_name`)
+    pub fn to_zsh_completion(&self) -> String {
+        let mut s = String::new();
+        s.push_str(&format!("#compdef {}\n", self.name));
+        s.push_str(&format!(
+            "# zsh completion for {}, {}\n",
+            self.name, GENERATED_BY
+        ));
+
+        for path in walk(self) {
+            let spec = path[path.len() - 1];
+            let func = zsh_function(&path);
+
+            let mut specs: Vec<String> =
+                spec.flags.iter().map(|flag| zsh_flag(spec, flag)).collect();
+            if spec.subcommands.is_empty() {
+                specs.extend(zsh_params(&spec.params));
+            } else {
+                specs.push("'1: :->command'".to_string());
+                specs.push("'*:: :->args'".to_string());
+            }
+
+            s.push_str(&format!("\n{}() {{\n", func));
+            if spec.subcommands.is_empty() {
+                s.push_str("    _arguments");
+            } else {
+                s.push_str("    local context state state_descr line\n");
+                s.push_str("    typeset -A opt_args\n\n");
+                s.push_str("    _arguments -C");
+            }
+            for spec in &specs {
+                s.push_str(&format!(" \\\n        {}", spec));
+            }
+            s.push('\n');
+
+            if !spec.subcommands.is_empty() {
+                s.push_str("\n    case $state in\n");
+                s.push_str("        command)\n");
+                s.push_str("            local -a commands=(\n");
+                for sub in &spec.subcommands {
+                    for name in sub.names() {
+                        let described = match &sub.help {
+                            Some(help) => format!("{}:{}", zsh_escape(name), help),
+                            None => zsh_escape(name),
+                        };
+                        s.push_str(&format!("                {}\n", posix_quote(&described)));
+                    }
+                }
+                s.push_str("            )\n");
+                s.push_str("            _describe -t commands 'command' commands\n");
+                s.push_str("            ;;\n");
+                s.push_str("        args)\n");
+                s.push_str("            case $line[1] in\n");
+                for sub in &spec.subcommands {
+                    let mut sub_path = path.clone();
+                    sub_path.push(sub);
+                    let patterns: Vec<_> = sub.names().map(posix_quote).collect();
+                    s.push_str(&format!(
+                        "                {}) {} ;;\n",
+                        patterns.join("|"),
+                        zsh_function(&sub_path)
+                    ));
+                }
+                s.push_str("            esac\n");
+                s.push_str("            ;;\n");
+                s.push_str("    esac\n");
+            }
+            s.push_str("}\n");
+        }
+
+        s.push_str(&format!("\n_{} \"$@\"\n", ident(&self.name)));
+        s
+    }
+
+    /// Generate fish completion script (`complete -c name ...`)
+    pub fn to_fish_completion(&self) -> String {
+        let name = fish_quote(&self.name);
+        let mut s = String::new();
+        s.push_str(&format!(
+            "# fish completion for {}, {}\n",
+            self.name, GENERATED_BY
+        ));
+        s.push_str(&format!("complete -c {} -f\n", name));
+
+        for path in walk(self) {
+            let spec = path[path.len() - 1];
+            let condition = fish_condition(&path);
+            let complete = match &condition {
+                Some(condition) => format!("complete -c {} -n {}", name, fish_quote(condition)),
+                None => format!("complete -c {}", name),
+            };
+
+            for flag in &spec.flags {
+                let mut line = complete.clone();
+                if let Some(short) = flag.short {
+                    line.push_str(&format!(" -s {}", fish_quote(&short.to_string())));
+                }
+                if let Some(long) = &flag.long {
+                    line.push_str(&format!(" -l {}", fish_quote(long)));
+                }
+                match &flag.value {
+                    Some(ValueType::Path) => line.push_str(" -r -F"),
+                    Some(ValueType::Choice(choices)) => {
+                        line.push_str(&format!(" -x -a {}", fish_quote(&choices.join(" "))))
+                    }
+                    Some(_) => line.push_str(" -x"),
+                    None => {}
+                }
+                if let Some(help) = &flag.help {
+                    line.push_str(&format!(" -d {}", fish_quote(help)));
+                }
+                s.push_str(&line);
+                s.push('\n');
+            }
+
+            for sub in &spec.subcommands {
+                for sub_name in sub.names() {
+                    let mut line = format!("{} -a {}", complete, fish_quote(sub_name));
+                    if let Some(help) = &sub.help {
+                        line.push_str(&format!(" -d {}", fish_quote(help)));
+                    }
+                    s.push_str(&line);
+                    s.push('\n');
+                }
+            }
+
+            for param in completed_params(spec) {
+                match &param.value {
+                    ValueType::Path => s.push_str(&format!("{} -F\n", complete)),
+                    ValueType::Choice(choices) => s.push_str(&format!(
+                        "{} -a {}\n",
+                        complete,
+                        fish_quote(&choices.join(" "))
+                    )),
+                    _ => {}
+                }
+            }
+        }
+
+        s
+    }
+}
+
+// path as used in the bash script (`git log`)
+fn bash_path(path: &[&CmdSpec]) -> String {
+    path.iter()
+        .map(|spec| spec.name.as_ref())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// `compgen` call completing the value, `None` if the value can not be completed
+fn bash_compgen(value: &ValueType) -> Option<String> {
+    match value {
+        ValueType::Path => Some("compgen -f -- \"${cur}\"".to_string()),
+        ValueType::Choice(choices) => Some(format!(
+            "compgen -W {} -- \"${{cur}}\"",
+            posix_quote(&choices.join(" "))
+        )),
+        _ => None,
+    }
+}
+
+// function name (`_git__log`)
+fn zsh_function(path: &[&CmdSpec]) -> String {
+    let names: Vec<String> = path.iter().map(|spec| ident(&spec.name)).collect();
+    format!("_{}", names.join("__"))
+}
+
+// escape characters special in `_arguments`, `_describe` specifications
+fn zsh_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '[' | ']' | ':' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+// `_arguments` action completing the value
+fn zsh_action(value: &ValueType) -> String {
+    match value {
+        ValueType::Path => "_files".to_string(),
+        ValueType::Choice(choices) => format!(
+            "({})",
+            choices
+                .iter()
+                .map(|choice| zsh_escape(choice))
+                .collect::<Vec<_>>()
+                .join(" ")
+        ),
+        _ => " ".to_string(),
+    }
+}
+
+// `_arguments` flag specification (`'(-v --verbose)'{-v,--verbose}'[be verbose]'`)
+fn zsh_flag(spec: &CmdSpec, flag: &FlagSpec) -> String {
+    let names = flag_names(flag);
+
+    // mutually exclusive flags, own names too unless the flag is repeatable (`*`)
+    let id = flag.id();
+    let repeatable = flag.max_count != Some(1);
+    let mut exclusive = if names.len() > 1 && !repeatable {
+        names.clone()
+    } else {
+        Vec::new()
+    };
+    for group in spec
+        .exclusive
+        .iter()
+        .filter(|group| group.iter().any(|g| *g == id))
+    {
+        for other in group.iter().filter(|g| **g != id) {
+            if let Some(other) = spec.flags.iter().find(|f| f.id() == *other) {
+                exclusive.extend(flag_names(other));
+            }
+        }
+    }
+
+    let mut prefix = String::new();
+    if !exclusive.is_empty() {
+        prefix.push_str(&format!("({})", exclusive.join(" ")));
+    }
+    if repeatable {
+        prefix.push('*');
+    }
+
+    let mut suffix = String::new();
+    if let Some(help) = &flag.help {
+        suffix.push_str(&format!("[{}]", zsh_escape(help)));
+    }
+    if let Some(value) = &flag.value {
+        let label = names
+            .last()
+            .map_or("value", |name| name.trim_start_matches('-'));
+        suffix.push_str(&format!(":{}:{}", label, zsh_action(value)));
+    }
+
+    if names.len() > 1 {
+        let quote = |s: &str| {
+            if s.is_empty() {
+                String::new()
+            } else {
+                posix_quote(s).into_owned()
+            }
+        };
+        format!(
+            "{}{{{}}}{}",
+            quote(&prefix),
+            names.join(","),
+            quote(&suffix)
+        )
+    } else {
+        posix_quote(&format!("{}{}{}", prefix, names.join(""), suffix)).into_owned()
+    }
+}
+
+// `_arguments` positional specifications (`'1:dest:_files'`, `'*:files:_files'`)
+fn zsh_params(params: &[ParamSpec]) -> Vec<String> {
+    let mut v = Vec::new();
+    for (position, param) in (1..).zip(params) {
+        let action = zsh_action(&param.value);
+        let name = zsh_escape(&param.name);
+        if param.max.is_none_or(|max| max > 1) {
+            v.push(posix_quote(&format!("*:{}:{}", name, action)).into_owned());
+            break;
+        }
+        let optional = if param.min == 0 { ":" } else { "" };
+        v.push(posix_quote(&format!("{}:{}{}:{}", position, optional, name, action)).into_owned());
+    }
+    v
+}
+
+// `complete -n` condition, subcommands of the path are present, no subcommand of the last one
+fn fish_condition(path: &[&CmdSpec]) -> Option<String> {
+    let mut conditions: Vec<String> = path[1..]
+        .iter()
+        .map(|spec| {
+            let names: Vec<_> = spec.names().map(fish_quote).collect();
+            format!("__fish_seen_subcommand_from {}", names.join(" "))
+        })
+        .collect();
+
+    let spec = path[path.len() - 1];
+    if !spec.subcommands.is_empty() {
+        if path.len() == 1 {
+            conditions.push("__fish_use_subcommand".to_string());
+        } else {
+            let names: Vec<_> = spec
+                .subcommands
+                .iter()
+                .flat_map(|sub| sub.names())
+                .map(fish_quote)
+                .collect();
+            conditions.push(format!(
+                "not __fish_seen_subcommand_from {}",
+                names.join(" ")
+            ));
+        }
+    }
+
+    (!conditions.is_empty()).then(|| conditions.join("; and "))
+}
@@ -0,0 +1,105 @@
+// compare with `src/snapshots/<name>`, `UPDATE_SNAPSHOTS=1` rewrites the snapshot
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/snapshots")
+        .join(name);
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, actual).unwrap();
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_default();
+    assert_eq!(actual, expected, "snapshot {} differs", name);
+}
+
+fn tmux_spec() -> crate::CmdSpec<'static> {
+    use crate::{CmdSpec, FlagSpec, ParamSpec, ValueType};
+
+    CmdSpec::new("tmux")
+        .flag(FlagSpec::short('u').max_count(1).help("force UTF-8"))
+        .flag(
+            FlagSpec::short('f')
+                .value(ValueType::Path)
+                .help("configuration file"),
+        )
+        .flag(
+            FlagSpec::short('L')
+                .value(ValueType::String)
+                .max_count(1)
+                .help("socket name"),
+        )
+        .subcommand(
+            CmdSpec::new("new-session")
+                .alias("new")
+                .help("create a new session")
+                .flag(FlagSpec::short('d').max_count(1).help("detached"))
+                .flag(
+                    FlagSpec::short('s')
+                        .value(ValueType::String)
+                        .help("session name"),
+                )
+                .param(ParamSpec::new("command").optional()),
+        )
+        .subcommand(
+            CmdSpec::new("set-option")
+                .alias("set")
+                .help("set an option")
+                .flag(FlagSpec::short('g').max_count(1).help("global [server]"))
+                .flag(
+                    FlagSpec::long("mode")
+                        .value(ValueType::Choice(vec!["vi".into(), "emacs".into()])),
+                )
+                .exclusive(["-g", "--mode"])
+                .param(ParamSpec::new("file").value(ValueType::Path).arity(0, None)),
+        )
+        .subcommand(
+            CmdSpec::new("source-file")
+                .help("execute commands from file, it's like `.`")
+                .subcommand(CmdSpec::new("now").flag(FlagSpec::long("quiet").with_short('q'))),
+        )
+}
+
+#[test]
+fn completion_bash() {
+    let script = tmux_spec().to_bash_completion();
+    assert_snapshot("tmux.bash", &script);
+
+    // script is valid bash
+    #[cfg(unix)]
+    assert_syntax("bash", "-n", &script);
+}
+
+// syntax check of the script by `shell` (`flag` = no execution), skipped if the shell is not
+// installed
+#[cfg(unix)]
+fn assert_syntax(shell: &str, flag: &str, script: &str) {
+    let status = crate::Cmd::with_name(shell)
+        .push_flag(flag)
+        .stdin(crate::Redirect::bytes(script.as_bytes()))
+        .status();
+    match status {
+        Ok(status) => assert!(status.success(), "{} syntax check failed", shell),
+        Err(crate::CmdError::Io { error, .. }) if error.kind() == std::io::ErrorKind::NotFound => {
+            eprintln!("{} not installed, syntax check skipped", shell);
+        }
+        Err(error) => panic!("{}", error),
+    }
+}
+
+#[test]
+fn completion_zsh() {
+    let script = tmux_spec().to_zsh_completion();
+    assert_snapshot("tmux.zsh", &script);
+
+    // script is valid zsh
+    #[cfg(unix)]
+    assert_syntax("zsh", "-n", &script);
+}
+
+#[test]
+fn completion_fish() {
+    let script = tmux_spec().to_fish_completion();
+    assert_snapshot("tmux.fish", &script);
+
+    // script is valid fish
+    #[cfg(unix)]
+    assert_syntax("fish", "--no-execute", &script);
+}
@@ -0,0 +1,90 @@
+// git with every field kind, `log` subcommand
+#[derive(crate::IntoCmd)]
+#[cmd(name = "git")]
+struct Git {
+    #[cmd(short)]
+    verbose: bool,
+    #[cmd(long = "git-dir")]
+    dir: Option<String>,
+    #[cmd(short = 'c')]
+    config: Vec<String>,
+    #[cmd(env = "GIT_PAGER")]
+    pager: Option<&'static str>,
+    #[cmd(subcommand)]
+    command: Log,
+    #[cmd(skip)]
+    _comment: String,
+}
+
+#[derive(crate::IntoCmd)]
+struct Log {
+    #[cmd(long)]
+    max_count: u32,
+    #[cmd(long)]
+    no_merges: bool,
+    #[cmd(param)]
+    paths: Vec<String>,
+}
+
+// `git log --max-count 5` without any optional argument
+fn git_log() -> Git {
+    Git {
+        verbose: false,
+        dir: None,
+        config: Vec::new(),
+        pager: None,
+        command: Log {
+            max_count: 5,
+            no_merges: false,
+            paths: Vec::new(),
+        },
+        _comment: String::new(),
+    }
+}
+
+#[test]
+fn derive_into_cmd() {
+    use crate::Cmd;
+
+    let git = Git {
+        verbose: true,
+        config: vec!["a=1".into(), "b=2".into()],
+        pager: Some("cat"),
+        command: Log {
+            paths: vec!["src".into()],
+            ..git_log().command
+        },
+        ..git_log()
+    };
+    let cmd = Cmd::from(&git);
+    assert_eq!(
+        cmd.to_string(),
+        "GIT_PAGER=cat git -v -c a=1 -c b=2 log --max-count 5 src"
+    );
+}
+
+#[test]
+fn derive_into_cmd_skipped() {
+    use crate::Cmd;
+
+    // `false` flags, `None` options and empty lists are not added
+    assert_eq!(Cmd::from(&git_log()).to_string(), "git log --max-count 5");
+}
+
+#[test]
+fn derive_into_cmd_long() {
+    use crate::Cmd;
+
+    let git = Git {
+        dir: Some("/tmp/repo".into()),
+        command: Log {
+            no_merges: true,
+            ..git_log().command
+        },
+        ..git_log()
+    };
+    assert_eq!(
+        Cmd::from(&git).to_string(),
+        "git --git-dir /tmp/repo log --max-count 5 --no-merges"
+    );
+}
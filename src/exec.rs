@@ -0,0 +1,202 @@
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::process::{ExitStatus, Output};
+use std::string::FromUtf8Error;
+use std::time::Duration;
+
+/// Executed command result: exit status, captured output, execution time
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CmdOutput {
+    /// exit status of the process
+    pub status: ExitStatus,
+    /// captured standard output (empty if not captured)
+    pub stdout: Vec<u8>,
+    /// captured standard error (empty if not captured)
+    pub stderr: Vec<u8>,
+    /// time from spawning till exit of the process
+    pub duration: Duration,
+}
+
+impl CmdOutput {
+    /// Create `CmdOutput` from [`std::process::Output`]
+    pub fn new(output: Output, duration: Duration) -> Self {
+        CmdOutput {
+            status: output.status,
+            stdout: output.stdout,
+            stderr: output.stderr,
+            duration,
+        }
+    }
+
+    /// Create `CmdOutput` with given exit code and output, duration is zero (used by
+    /// [`MockExecutor`][`crate::executor::MockExecutor`], replayed cassettes)
+    ///
+    /// Unix exit codes are 0..=255, codes out of the range are reported as 255. Available on
+    /// unix and windows only, other platforms (e.g. wasm) can not create exit statuses.
+    ///
+    /// # Examples
+    /// ```
+    /// use cmd_builder::CmdOutput;
+    ///
+    /// let output = CmdOutput::from_code(1, "", "no server running\n");
+    /// assert_eq!(output.code(), Some(1));
+    /// assert_eq!(output.stderr_lossy(), "no server running\n");
+    /// ```
+    #[cfg(any(unix, windows))]
+    pub fn from_code<T, U>(code: i32, stdout: T, stderr: U) -> Self
+    where
+        T: Into<Vec<u8>>,
+        U: Into<Vec<u8>>,
+    {
+        CmdOutput {
+            status: exit_status(code),
+            stdout: stdout.into(),
+            stderr: stderr.into(),
+            duration: Duration::ZERO,
+        }
+    }
+
+    /// Returns `true` if the process exited successfully (exit code 0)
+    pub fn success(&self) -> bool {
+        self.status.success()
+    }
+
+    /// Exit code of the process, `None` if terminated by a signal
+    pub fn code(&self) -> Option<i32> {
+        self.status.code()
+    }
+
+    /// Standard output as string (invalid UTF-8 sequences are replaced with `U+FFFD`)
+    pub fn stdout_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.stdout)
+    }
+
+    /// Standard error as string (invalid UTF-8 sequences are replaced with `U+FFFD`)
+    pub fn stderr_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.stderr)
+    }
+}
+
+// exit status of a process exited with given code, unix exit codes are 0..=255, codes out of
+// the range are reported as 255 (so they never become success)
+#[cfg(unix)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::unix::process::ExitStatusExt;
+    let code = if (0..=255).contains(&code) { code } else { 255 };
+    ExitStatus::from_raw(code << 8)
+}
+
+// exit status of a process exited with given code
+#[cfg(windows)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::windows::process::ExitStatusExt;
+    ExitStatus::from_raw(code as u32)
+}
+
+/// Executed commands sequence result (see [`CmdList::run()`][`crate::CmdList::run()`])
+#[derive(Debug)]
+pub struct CmdListOutput {
+    /// result of each command in the list order, `None` if command was skipped
+    pub outputs: Vec<Option<Result<CmdOutput, CmdError>>>,
+}
+
+impl CmdListOutput {
+    /// Returns `true` if the last executed command exited successfully (same as the sequence
+    /// status in shell)
+    pub fn success(&self) -> bool {
+        self.outputs
+            .iter()
+            .rev()
+            .flatten()
+            .next()
+            .is_some_and(|output| output.as_ref().is_ok_and(|output| output.success()))
+    }
+
+    /// Number of executed (not skipped) commands
+    pub fn executed(&self) -> usize {
+        self.outputs.iter().flatten().count()
+    }
+}
+
+/// Command execution error, each variant contains the rendered command line
+#[derive(Debug)]
+pub enum CmdError {
+    /// process could not be spawned or waited for
+    Io { command: String, error: io::Error },
+    /// process exited with non-zero exit code or was terminated by a signal
+    Failed { command: String, output: CmdOutput },
+    /// standard output is not valid UTF-8
+    Utf8 {
+        command: String,
+        error: FromUtf8Error,
+    },
+    /// command does not match any recorded command (see
+    /// [`Replayer`][`crate::cassette::Replayer`]), `diff` is between the closest recorded
+    /// command (`- `) and the executed one (`+ `)
+    Replay { command: String, diff: String },
+}
+
+impl CmdError {
+    /// Rendered command line of the failed command
+    pub fn command(&self) -> &str {
+        match self {
+            CmdError::Io { command, .. } => command,
+            CmdError::Failed { command, .. } => command,
+            CmdError::Utf8 { command, .. } => command,
+            CmdError::Replay { command, .. } => command,
+        }
+    }
+
+    /// Exit code of the failed command, if it was executed and not terminated by a signal
+    pub fn code(&self) -> Option<i32> {
+        match self {
+            CmdError::Failed { output, .. } => output.code(),
+            _ => None,
+        }
+    }
+
+    /// Captured standard error of the failed command
+    pub fn stderr(&self) -> Option<Cow<'_, str>> {
+        match self {
+            CmdError::Failed { output, .. } => Some(output.stderr_lossy()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CmdError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CmdError::Io { command, error } => write!(f, "`{}`: {}", command, error),
+            CmdError::Failed { command, output } => {
+                match output.code() {
+                    Some(code) => write!(f, "`{}`: exit code {}", command, code)?,
+                    None => write!(f, "`{}`: terminated by signal", command)?,
+                }
+                let stderr = output.stderr_lossy();
+                let stderr = stderr.trim_end();
+                if !stderr.is_empty() {
+                    write!(f, ": {}", stderr)?;
+                }
+                Ok(())
+            }
+            CmdError::Utf8 { command, error } => write!(f, "`{}`: {}", command, error),
+            CmdError::Replay { command, diff } => {
+                write!(f, "`{}`: no recorded command matches\n{}", command, diff)
+            }
+        }
+    }
+}
+
+impl Error for CmdError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CmdError::Io { error, .. } => Some(error),
+            CmdError::Failed { .. } => None,
+            CmdError::Utf8 { error, .. } => Some(error),
+            CmdError::Replay { .. } => None,
+        }
+    }
+}
@@ -0,0 +1,87 @@
+// prints to stdout and stderr, exits with 2
+#[cfg(unix)]
+fn failing_cmd() -> crate::Cmd<'static> {
+    use crate::Cmd;
+
+    let mut cmd = Cmd::with_name("sh");
+    cmd.env("GREETING", "hello")
+        .opt("-c", "echo $GREETING; echo oops >&2; exit 2");
+    cmd
+}
+
+#[cfg(unix)]
+#[test]
+fn exec_output() {
+    let output = failing_cmd().output().unwrap();
+    assert!(!output.success());
+    assert_eq!(output.code(), Some(2));
+    assert_eq!(output.stdout_lossy(), "hello\n");
+    assert_eq!(output.stderr_lossy(), "oops\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn exec_run_failed() {
+    use crate::CmdError;
+
+    match failing_cmd().run() {
+        Err(err @ CmdError::Failed { .. }) => {
+            assert_eq!(
+                err.command(),
+                "GREETING=hello sh -c 'echo $GREETING; echo oops >&2; exit 2'"
+            );
+            assert_eq!(err.code(), Some(2));
+            assert_eq!(err.stderr().as_deref(), Some("oops\n"));
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn exec_status() {
+    use crate::Cmd;
+
+    let status = Cmd::with_name("true").status().unwrap();
+    assert!(status.success());
+}
+
+#[cfg(unix)]
+#[test]
+fn exec_output_string() {
+    use crate::Cmd;
+
+    let s = Cmd::with_name("printf")
+        .param("%s")
+        .param("x y")
+        .output_string();
+    assert_eq!(s.unwrap(), "x y");
+}
+
+#[cfg(unix)]
+#[test]
+fn exec_missing_binary() {
+    use crate::{Cmd, CmdError};
+
+    let err = Cmd::with_name("cmd-builder-missing-binary")
+        .run()
+        .unwrap_err();
+    assert!(matches!(err, CmdError::Io { .. }));
+    assert_eq!(err.command(), "cmd-builder-missing-binary");
+}
+
+#[cfg(unix)]
+#[test]
+fn exec_from_code() {
+    use crate::CmdOutput;
+
+    assert_eq!(CmdOutput::from_code(0, "", "").code(), Some(0));
+    assert_eq!(CmdOutput::from_code(255, "", "").code(), Some(255));
+
+    // out of range codes are never success
+    for code in [256, 512, -1, i32::MIN] {
+        let output = CmdOutput::from_code(code, "", "");
+        assert!(!output.success(), "{}", code);
+        assert_eq!(output.code(), Some(255));
+    }
+}
@@ -0,0 +1,252 @@
+//! Pluggable command execution: real processes, dry run, scripted mock outputs
+use super::exec::{CmdError, CmdOutput};
+use super::Cmd;
+use std::borrow::Cow;
+use std::io;
+use std::sync::{Mutex, PoisonError};
+
+/// any single argument
+const ANY_ARG: &str = "*";
+/// any remaining arguments (also none)
+const ANY_ARGS: &str = "..";
+
+/// Command execution, allows replacing spawning of processes in tests
+///
+/// Same semantics as [`Cmd::run()`]: non-zero exit code is an error ([`CmdError::Failed`]
+/// containing the output)
+///
+/// ```text
+/// RealExecutor - spawns processes (`Cmd::run()`)
+/// DryRun       - records commands, does not execute them (exit code 0, empty output)
+/// MockExecutor - records commands, returns outputs matched by program name and arguments
+/// ```
+pub trait Executor {
+    fn execute(&self, cmd: &Cmd) -> Result<CmdOutput, CmdError>;
+}
+
+impl<E: Executor + ?Sized> Executor for &E {
+    fn execute(&self, cmd: &Cmd) -> Result<CmdOutput, CmdError> {
+        (**self).execute(cmd)
+    }
+}
+
+impl<E: Executor + ?Sized> Executor for Box<E> {
+    fn execute(&self, cmd: &Cmd) -> Result<CmdOutput, CmdError> {
+        (**self).execute(cmd)
+    }
+}
+
+// executed commands, owned
+#[derive(Debug, Default)]
+struct Calls(Mutex<Vec<Cmd<'static>>>);
+
+impl Calls {
+    fn push(&self, cmd: &Cmd) {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(cmd.clone().into_owned());
+    }
+
+    fn to_vec(&self) -> Vec<Cmd<'static>> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+/// Executor spawning processes (see [`Cmd::run()`])
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RealExecutor;
+
+impl Executor for RealExecutor {
+    fn execute(&self, cmd: &Cmd) -> Result<CmdOutput, CmdError> {
+        cmd.run()
+    }
+}
+
+/// Executor recording all commands without executing them, each command succeeds with empty
+/// output
+///
+/// # Examples
+/// ```
+/// use cmd_builder::executor::{DryRun, Executor};
+/// use cmd_builder::Cmd;
+///
+/// let dry_run = DryRun::new();
+/// let output = dry_run.execute(Cmd::with_name("rm").param("-rf").param("/")).unwrap();
+/// assert!(output.success());
+/// assert_eq!(dry_run.calls()[0].to_string(), "rm -rf /");
+/// ```
+#[derive(Debug, Default)]
+pub struct DryRun {
+    calls: Calls,
+}
+
+impl DryRun {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Received commands in order
+    pub fn calls(&self) -> Vec<Cmd<'static>> {
+        self.calls.to_vec()
+    }
+}
+
+impl Executor for DryRun {
+    fn execute(&self, cmd: &Cmd) -> Result<CmdOutput, CmdError> {
+        self.calls.push(cmd);
+        Ok(CmdOutput::from_code(0, "", ""))
+    }
+}
+
+/// Command pattern of [`MockExecutor`]: program name (`argv[0]`) and arguments
+///
+/// ```text
+/// "*"   - any single argument
+/// ".."  - any remaining arguments (also none)
+/// other - exactly the same argument
+/// ```
+///
+/// Without arguments pattern any arguments match
+///
+/// # Examples
+/// ```
+/// use cmd_builder::executor::CmdPattern;
+/// use cmd_builder::Cmd;
+///
+/// let pattern = CmdPattern::new("tmux").args(["has-session", "-t", "*"]);
+/// assert!(pattern.matches(Cmd::with_name("tmux").param("has-session").opt("-t", "main")));
+/// assert!(!pattern.matches(Cmd::with_name("tmux").param("kill-server")));
+///
+/// let pattern = CmdPattern::new("tmux").args(["new-session", ".."]);
+/// assert!(pattern.matches(Cmd::with_name("tmux").param("new-session").push_flag("-d")));
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct CmdPattern<'a> {
+    /// program name (`argv[0]`, alias if the command uses alias)
+    pub program: Cow<'a, str>,
+
+    /// arguments patterns, `None` = any arguments
+    pub args: Option<Vec<Cow<'a, str>>>,
+}
+
+impl<'a> CmdPattern<'a> {
+    pub fn new<S: Into<Cow<'a, str>>>(program: S) -> Self {
+        CmdPattern {
+            program: program.into(),
+            args: None,
+        }
+    }
+
+    /// Set `CmdPattern.args` field
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<Cow<'a, str>>,
+    {
+        self.args = Some(args.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Returns `true` if the command matches the pattern
+    pub fn matches(&self, cmd: &Cmd) -> bool {
+        let argv = cmd.to_argv();
+        let Some((program, args)) = argv.split_first() else {
+            return false;
+        };
+        if *program != self.program {
+            return false;
+        }
+
+        let Some(patterns) = &self.args else {
+            return true;
+        };
+        let mut args = args.iter();
+        for pattern in patterns {
+            if pattern == ANY_ARGS {
+                return true;
+            }
+            match args.next() {
+                Some(arg) if pattern == ANY_ARG || arg == pattern => {}
+                _ => return false,
+            }
+        }
+        args.next().is_none()
+    }
+}
+
+/// Executor recording all commands and returning scripted outputs, the first matching
+/// [`CmdPattern`] is used (patterns are not consumed), command without matching pattern fails
+/// with [`CmdError::Io`] (`NotFound`)
+///
+/// # Examples
+/// ```
+/// use cmd_builder::executor::{CmdPattern, Executor, MockExecutor};
+/// use cmd_builder::{Cmd, CmdOutput};
+///
+/// let mock = MockExecutor::new()
+///     .on(
+///         CmdPattern::new("tmux").args(["has-session", "-t", "*"]),
+///         CmdOutput::from_code(1, "", "can't find session\n"),
+///     )
+///     .on(CmdPattern::new("tmux"), CmdOutput::from_code(0, "", ""));
+///
+/// let mut cmd = Cmd::with_name("tmux");
+/// cmd.param("has-session").opt("-t", "main");
+/// assert_eq!(mock.execute(&cmd).unwrap_err().code(), Some(1));
+/// assert!(mock.execute(Cmd::with_name("tmux").param("new-session")).is_ok());
+/// assert!(mock.execute(&Cmd::with_name("screen")).is_err());
+///
+/// let calls = mock.calls();
+/// assert_eq!(calls.len(), 3);
+/// assert_eq!(calls[0], cmd);
+/// ```
+#[derive(Debug, Default)]
+pub struct MockExecutor<'a> {
+    /// patterns and their outputs, in order
+    pub outputs: Vec<(CmdPattern<'a>, CmdOutput)>,
+    calls: Calls,
+}
+
+impl<'a> MockExecutor<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add output returned for commands matching the pattern
+    pub fn on(mut self, pattern: CmdPattern<'a>, output: CmdOutput) -> Self {
+        self.outputs.push((pattern, output));
+        self
+    }
+
+    /// Received commands in order
+    pub fn calls(&self) -> Vec<Cmd<'static>> {
+        self.calls.to_vec()
+    }
+}
+
+impl<'a> Executor for MockExecutor<'a> {
+    fn execute(&self, cmd: &Cmd) -> Result<CmdOutput, CmdError> {
+        self.calls.push(cmd);
+
+        let command = cmd.to_string();
+        let output = self
+            .outputs
+            .iter()
+            .find(|(pattern, _)| pattern.matches(cmd))
+            .map(|(_, output)| output.clone())
+            .ok_or_else(|| CmdError::Io {
+                command: command.clone(),
+                error: io::Error::new(io::ErrorKind::NotFound, "no mock output for the command"),
+            })?;
+
+        if output.success() {
+            Ok(output)
+        } else {
+            Err(CmdError::Failed { command, output })
+        }
+    }
+}
@@ -0,0 +1,112 @@
+// code under test, executor is a parameter
+fn ensure_session<E: crate::executor::Executor>(
+    executor: &E,
+    name: &str,
+) -> Result<bool, crate::CmdError> {
+    use crate::{Cmd, CmdError};
+
+    let mut has_session = Cmd::with_name("tmux");
+    has_session.param("has-session").opt("-t", name);
+    match executor.execute(&has_session) {
+        Ok(_) => return Ok(false),
+        Err(CmdError::Failed { .. }) => {}
+        Err(err) => return Err(err),
+    }
+    executor.execute(
+        Cmd::with_name("tmux")
+            .param("new-session")
+            .push_flag("-d")
+            .opt("-s", name),
+    )?;
+    Ok(true)
+}
+
+// `main` session exists, other sessions are created
+fn tmux_mock() -> crate::executor::MockExecutor<'static> {
+    use crate::executor::{CmdPattern, MockExecutor};
+    use crate::CmdOutput;
+
+    MockExecutor::new()
+        .on(
+            CmdPattern::new("tmux").args(["has-session", "-t", "main"]),
+            CmdOutput::from_code(0, "", ""),
+        )
+        .on(
+            CmdPattern::new("tmux").args(["has-session", ".."]),
+            CmdOutput::from_code(1, "", "can't find session\n"),
+        )
+        .on(
+            CmdPattern::new("tmux").args(["new-session", "*", "-s", "*"]),
+            CmdOutput::from_code(0, "", ""),
+        )
+}
+
+#[test]
+fn mock_executor() {
+    use crate::Cmd;
+
+    let mock = tmux_mock();
+    assert!(!ensure_session(&mock, "main").unwrap());
+    assert!(ensure_session(&mock, "work").unwrap());
+    let rendered: Vec<String> = mock.calls().iter().map(Cmd::to_string).collect();
+    assert_eq!(
+        rendered,
+        [
+            "tmux has-session -t main",
+            "tmux has-session -t work",
+            "tmux new-session -d -s work"
+        ]
+    );
+}
+
+#[test]
+fn mock_executor_no_match() {
+    use crate::executor::Executor;
+    use crate::{Cmd, CmdError};
+
+    let err = tmux_mock().execute(&Cmd::with_name("tmux")).unwrap_err();
+    assert!(matches!(err, CmdError::Io { .. }));
+    assert_eq!(err.command(), "tmux");
+}
+
+#[test]
+fn dry_run_executor() {
+    use crate::executor::DryRun;
+
+    // every command succeeds
+    let dry_run = DryRun::new();
+    assert!(!ensure_session(&dry_run, "main").unwrap());
+    assert_eq!(dry_run.calls().len(), 1);
+}
+
+#[test]
+fn executor_trait_object() {
+    use crate::executor::{DryRun, Executor};
+
+    let executor: Box<dyn Executor> = Box::new(DryRun::new());
+    assert!(ensure_session(&executor, "main").is_ok());
+}
+
+#[cfg(unix)]
+#[test]
+fn real_executor() {
+    use crate::executor::{Executor, RealExecutor};
+    use crate::Cmd;
+
+    let output = RealExecutor
+        .execute(Cmd::with_name("echo").param("hello"))
+        .unwrap();
+    assert_eq!(output.stdout_lossy(), "hello\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn real_executor_failed() {
+    use crate::executor::{Executor, RealExecutor};
+    use crate::Cmd;
+
+    let err = RealExecutor
+        .execute(Cmd::with_name("sh").opt("-c", "exit 4"))
+        .unwrap_err();
+    assert_eq!(err.code(), Some(4));
+}
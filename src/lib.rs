@@ -37,8 +37,8 @@
 //! # Why
 //!
 //! * structure fields holding data as [`Cow<'a, str>`][std::borrow::Cow] type. Allowing modifying, or
-//! using constants.
-//! * ~~macro for generation~~
+//!   using constants.
+//! * macro for generation ([`cmd!`])
 //! * short or long flags can be used, short flags can be combined together
 //! * command name or command alias can be used
 //! * sometimes we need a temporary structure for holding command line before it will be executed
@@ -47,8 +47,24 @@
 //! # Modules
 //!
 //! * [`Cmd`] - Main struct, holding environment variables, command name, alias, flags, arguments,
-//! subcommads, separator etc
-//! * [`CmdList`] - Wrapper around [`Vec<Cmd>`] including separator
+//!   subcommads, separator etc
+//! * [`CmdList`] - Wrapper around [`Vec<Cmd>`] including separator ([`Separator`])
+//! * [`OsCmd`] - [`Cmd`] variant holding [`OsStr`][`std::ffi::OsStr`] (non UTF-8) arguments
+//! * [`cmd!`] - declarative `Cmd` creation (envs, flags, options, params, conditionals, subcommands)
+//! * `IntoCmd` - `#[derive(IntoCmd)]`, `Cmd` from options struct (`derive` feature)
+//! * [`Pipeline`] - commands connected by pipes (`a | b | c`)
+//! * [`CmdOutput`], [`CmdError`] - command execution result and error
+//! * [`executor`] - [`Executor`][`executor::Executor`] trait: real processes, dry run, mock outputs for tests (unix, windows)
+//! * [`cassette`] - record executed commands and their output into a file, replay them offline (unix, windows)
+//! * [`CmdSpec`] - program specification (flags, options, parameters, subcommands) for validation
+//! * [`completion`] - bash, zsh, fish completion scripts generated from [`CmdSpec`]
+//! * [`Redirect`] - stdin, stdout, stderr configuration (inherit, null, pipe, file, bytes)
+//! * `tokio_exec` - asynchronous execution (`tokio` feature)
+//! * [`arg`] - [`Arg`] typed argument (flag, option, parameter, separator, raw)
+//! * [`ScriptOptions`] - options of `CmdList::to_script()` (shebang, `set -eu`, working directory, echo)
+//! * [`Shell`] - target shell of `render()` (POSIX sh, fish, PowerShell, cmd.exe)
+//! * [`quote`] - POSIX sh, fish, PowerShell, Windows (`CommandLineToArgvW`, cmd.exe) quoting of single words
+//! * [`parse`] - command line parsing errors
 //!
 //! # Details
 //!
@@ -68,23 +84,83 @@
 //! [subcommand [...]]      - subcommand
 //! ```
 //!
+//! # Serialization (`serde` feature)
+//!
+//! [`Cmd`], [`CmdList`] and their field types implement `Serialize`, `Deserialize`. Fields
+//! have the same names as struct fields, missing fields are set to `Default` values (so
+//! `combine_short_flags`, `use_alias` are `true`), strings are deserialized owned (so
+//! `Cmd<'static>` is `DeserializeOwned`, required by `toml::from_str()`). Enums use
+//! snake_case variant names, unit variants are strings, other variants are single key maps.
+//!
+//! ```text
+//! Cmd:
+//! envs                      - [[key, value], ...]
+//! env_clear                 - bool
+//! env_removes               - [key, ...]
+//! current_dir               - string
+//! name, alias               - string
+//! flags                     - [string, ...]
+//! flags_short               - string of flag characters ("la" = `-la`)
+//! args                      - [Arg, ...]
+//! subcommands               - CmdList
+//! separator                 - string (" ")
+//! flags_args_separator      - string ("--")
+//! auto_flags_args_separator - bool
+//! option_style              - "separate" | "equals" | "attached"
+//! combine_short_flags       - bool
+//! use_alias                 - bool
+//! stdin, stdout, stderr     - Redirect
+//!
+//! CmdList:
+//! commands                  - [Cmd, ...]
+//! separator                 - Separator, null = commands joined by space (default "semicolon")
+//!
+//! Arg:       {"flag": s} | {"option": {"key": s, "value": s, "style": option_style}} |
+//!            {"param": s} | "separator" | {"raw": s}
+//! Separator: "semicolon" | "escaped_semicolon" | "newline" | "and" | "or" | "pipe" |
+//!            {"custom": s}
+//! Redirect:  "inherit" | "null" | "piped" | {"file": s} | {"append": s} |
+//!            {"bytes": [u8, ...]} | "stdout"
+//! ```
+//!
+//! ```json
+//! {
+//!   "envs": [["LANG", "C"]],
+//!   "name": "git",
+//!   "flags_short": "v",
+//!   "args": [{"flag": "--all"}, "separator", {"param": "file"}],
+//!   "subcommands": {"commands": [{"name": "status"}]}
+//! }
+//! ```
+//!
 //! # [`Cmd`]
 //!
 //! New:
 //! * [`::new()`][`Cmd::new()`] - Create default (short flags will be combined, command alias instead of
 //! * [`::default()`][`Cmd::default()`] - Create default (short flags will be combined, command alias instead of
-//! name will be used)
+//!   name will be used)
 //! * [`::new_full()`][`Cmd::new_full()`] - Create with command name and not combined short flags will be used
 //! * [`::with_name()`][`Cmd::with_name()`] - Create with name
+//! * [`::parse_shell()`][`Cmd::parse_shell()`] - Create from POSIX sh command line (also `str::parse()`)
 //!
 //! Setters:
 //! * [`.name()`][`Cmd::name()`] - set command name
 //! * [`.alias()`][`Cmd::alias()`] - set command alias
 //! * [`.env()`][`Cmd::env()`] - add environment variable
+//! * [`.env_clear()`][`Cmd::env_clear()`] - clear inherited environment variables
+//! * [`.env_remove()`][`Cmd::env_remove()`] - remove inherited environment variable
+//! * [`.current_dir()`][`Cmd::current_dir()`] - set working directory
 //! * [`.push_flag()`][`Cmd::push_flag()`] - add flag
 //! * [`.push_flag_short()`][`Cmd::push_flag_short()`] - add short flag
 //! * [`.push_option()`][`Cmd::push_option()`] - add option
+//! * [`.push_option_ext()`][`Cmd::push_option_ext()`] - add option with given style ([`OptionStyle`])
+//! * [`.option_style()`][`Cmd::option_style()`] - set default options style
 //! * [`.push_param()`][`Cmd::push_param()`] - add param
+//! * [`.push_raw()`][`Cmd::push_raw()`] - add argument with unknown role
+//! * [`.push_arg()`][`Cmd::push_arg()`] - add typed argument ([`Arg`])
+//! * [`.flags_args_separator()`][`Cmd::flags_args_separator()`] - set flags, args separator (`--`)
+//! * [`.auto_flags_args_separator()`][`Cmd::auto_flags_args_separator()`] - insert separator only if needed
+//! * [`.stdin()`][`Cmd::stdin()`], [`.stdout()`][`Cmd::stdout()`], [`.stderr()`][`Cmd::stderr()`] - set stdio ([`Redirect`])
 //! * [`.push_cmd()`][`Cmd::push_cmd()`] - add subcommand
 //! * [`.push_cmds()`][`Cmd::push_cmds()`] - add subcommands
 //! * [`.arg()`][`Cmd::arg()`] - add arg
@@ -93,34 +169,111 @@
 //!
 //! Output:
 //! * [`.to_vec()`][`Cmd::to_vec()`] - [`Cmd`] to [`Vec<Cow<'a, str>>`][`Vec`]
+//! * [`.to_argv()`][`Cmd::to_argv()`] - [`Cmd`] to [`Vec<Cow<'a, str>>`][`Vec`] without environment variables
+//! * [`.to_envs()`][`Cmd::to_envs()`] - environment variables of the command and it's subcommands
+//! * [`.into_owned()`][`Cmd::into_owned()`] - [`Cmd`] owning all strings (`Cmd<'static>`)
 //! * [`.to_command()`][`Cmd::to_command()`] - [`Cmd`] struct to [`Command`][`std::process::Command`]
+//! * [`.try_to_command()`][`Cmd::try_to_command()`] - [`Cmd`] struct to [`Command`][`std::process::Command`], opening stdio files
+//! * [`.validate()`][`Cmd::validate()`] - check against specification ([`CmdSpec`])
+//!
+//! Execution:
+//! * [`.output()`][`Cmd::output()`] - execute, capture output ([`CmdOutput`])
+//! * [`.run()`][`Cmd::run()`] - execute, capture output, non-zero exit code is an error ([`CmdError`])
+//! * [`.status()`][`Cmd::status()`] - execute with inherited stdio
+//! * [`.output_string()`][`Cmd::output_string()`] - execute, return stdout as [`String`]
+//! * [`.to_shell_string()`][`Cmd::to_shell_string()`] - [`Cmd`] to POSIX sh quoted [`String`] (used by `Display`)
+//! * [`.to_windows_string()`][`Cmd::to_windows_string()`], [`.to_cmd_exe_string()`][`Cmd::to_cmd_exe_string()`] - [`Cmd`] to Windows command line, for cmd.exe
+//! * [`.render()`][`Cmd::render()`] - [`Cmd`] to command line for given [`Shell`] (POSIX sh, fish, PowerShell, cmd.exe)
 //!
 //! # [`CmdList`]
 //!
 //! New:
 //! * [`::new()`][`CmdList::new()`] -
 //! * [`::default()`][`CmdList::default()`] -
+//! * [`::parse_tmux()`][`CmdList::parse_tmux()`] - Create from tmux command sequence
 //!
 //! Setters:
 //! * [`.push()`][`CmdList::push()`] -
 //! * [`.cmd()`][`CmdList::cmd()`] -
-//! * [`.separator()`][`CmdList::separator()`] -
+//! * [`.separator()`][`CmdList::separator()`] - set separator ([`Separator`])
 //!
 //! Output:
 //! * [`.to_vec()`][`CmdList::to_vec()`] -
 //! * [`.to_command_vec()`][`CmdList::to_command_vec()`] -
 //!
+//! Execution:
+//! * [`.run()`][`CmdList::run()`] - execute using separator semantics (`;`, `&&`, `||`, `|`)
+//! * [`.run_ext()`][`CmdList::run_ext()`] - execute using given semantics ([`RunPolicy`])
+//! * [`.to_shell_string()`][`CmdList::to_shell_string()`] - POSIX sh quoted, separators unquoted (used by `Display`)
+//! * [`.render()`][`CmdList::render()`] - command line for given [`Shell`], separators in the shell syntax
+//! * [`.to_script()`][`CmdList::to_script()`] - executable POSIX sh script ([`ScriptOptions`])
 //!
 //!
+//!
+pub mod arg;
+#[cfg(any(unix, windows))]
+pub mod cassette;
 pub mod cmd;
 pub mod cmd_list;
+pub mod completion;
+pub mod exec;
+#[cfg(any(unix, windows))]
+pub mod executor;
+#[doc(hidden)]
+pub mod macros;
+pub mod os_cmd;
+pub mod parse;
+pub mod pipeline;
+pub mod quote;
+pub mod script;
+pub mod shell;
+pub mod spec;
+pub mod stdio;
+#[cfg(feature = "tokio")]
+pub mod tokio_exec;
+
+pub use crate::arg::{Arg, OptionStyle};
+pub use crate::cmd::Cmd;
+pub use crate::cmd_list::{CmdList, RunPolicy, Separator};
+pub use crate::exec::{CmdError, CmdListOutput, CmdOutput};
+pub use crate::os_cmd::OsCmd;
+pub use crate::pipeline::{Pipeline, PipelineOutput};
+pub use crate::script::ScriptOptions;
+pub use crate::shell::Shell;
+pub use crate::spec::{CmdSpec, FlagSpec, ParamSpec, ValidationError, ValueType};
+pub use crate::stdio::Redirect;
+#[cfg(feature = "derive")]
+pub use cmd_builder_derive::IntoCmd;
+
+// generated code (`#[derive(IntoCmd)]`) refers to `::cmd_builder`
+#[cfg(all(test, feature = "derive"))]
+extern crate self as cmd_builder;
 
 #[cfg(test)]
 #[path = "."]
 mod cmd_builder_test {
+    mod arg_tests;
+    #[cfg(any(unix, windows))]
+    mod cassette_tests;
     mod cmd_list_tests;
     mod cmd_tests;
+    mod completion_tests;
+    #[cfg(feature = "derive")]
+    mod derive_tests;
+    mod exec_tests;
+    #[cfg(any(unix, windows))]
+    mod executor_tests;
+    mod macros_tests;
+    mod os_cmd_tests;
+    mod parse_tests;
+    mod pipeline_tests;
+    mod quote_tests;
+    mod script_tests;
+    #[cfg(feature = "serde")]
+    mod serde_tests;
+    mod shell_tests;
+    mod spec_tests;
+    mod stdio_tests;
+    #[cfg(feature = "tokio")]
+    mod tokio_exec_tests;
 }
-
-pub use crate::cmd::Cmd;
-pub use crate::cmd_list::CmdList;
@@ -0,0 +1,139 @@
+//! [`cmd!`][`crate::cmd!`] macro support
+use super::arg::{Arg, FLAGS_ARGS_SEPARATOR};
+use super::Cmd;
+
+/// Create `Cmd` declaratively, expands to `Cmd` builder calls
+///
+/// ```text
+/// KEY = value          - environment variable (before the name only)
+/// "name", {name}       - command name, literal or expression
+/// 'a'                  - short flag
+/// "--all", "-l"        - flag (literal starting with hyphen, parameter after `"--"`)
+/// "--"                 - flags, args separator
+/// "file"               - parameter (literal not starting with hyphen)
+/// "-t" = value         - option, key and value (rendered using `Cmd.option_style`)
+/// {expr}               - parameter (`Arg::Param`), rendered as is (see below)
+/// ..expr               - parameters, all items of `IntoIterator`
+/// if cond => { ... }   - arguments added only if `cond` is `true` (`else { ... }` optional)
+/// [ ... ]              - subcommand, same syntax as `cmd!`
+/// ```
+///
+/// Expression parameters are not checked for a leading hyphen, a value like `-rf` is read as a
+/// flag by the executed program. Put `"--"` before them or enable
+/// [`Cmd::auto_flags_args_separator()`] on the result.
+///
+/// # Examples
+/// ```
+/// use cmd_builder::cmd;
+///
+/// let file = String::from("-rf");
+/// let verbose = true;
+/// let cmd = cmd!(
+///     LANG = "C",
+///     "git",
+///     'v',
+///     "--no-pager",
+///     if verbose => { "--verbose" } else { "--quiet" },
+///     ["log", "-n" = "5", "--", {file}, ..["a", "b"]],
+/// );
+/// assert_eq!(
+///     cmd.to_string(),
+///     "LANG=C git -v --no-pager --verbose log -n 5 -- -rf a b"
+/// );
+/// ```
+#[macro_export]
+macro_rules! cmd {
+    // environment variables
+    (@envs $cmd:ident; $key:ident = $value:expr, $($rest:tt)*) => {
+        $cmd.env(stringify!($key), $value);
+        $crate::cmd!(@envs $cmd; $($rest)*);
+    };
+
+    // command name
+    (@envs $cmd:ident; {$name:expr} $(, $($rest:tt)*)?) => {
+        $cmd.name($name);
+        $crate::cmd!(@args $cmd; $($($rest)*)?);
+    };
+    (@envs $cmd:ident; $name:literal $(, $($rest:tt)*)?) => {
+        $cmd.name($name);
+        $crate::cmd!(@args $cmd; $($($rest)*)?);
+    };
+
+    // arguments
+    (@args $cmd:ident;) => {};
+    (@args $cmd:ident; if $cond:expr => {$($then:tt)*} else {$($else:tt)*} $(, $($rest:tt)*)?) => {
+        if $cond {
+            $crate::cmd!(@args $cmd; $($then)*);
+        } else {
+            $crate::cmd!(@args $cmd; $($else)*);
+        }
+        $crate::cmd!(@args $cmd; $($($rest)*)?);
+    };
+    (@args $cmd:ident; if $cond:expr => {$($then:tt)*} $(, $($rest:tt)*)?) => {
+        if $cond {
+            $crate::cmd!(@args $cmd; $($then)*);
+        }
+        $crate::cmd!(@args $cmd; $($($rest)*)?);
+    };
+    (@args $cmd:ident; [$($sub:tt)+] $(, $($rest:tt)*)?) => {
+        $cmd.push_cmd($crate::cmd!($($sub)+));
+        $crate::cmd!(@args $cmd; $($($rest)*)?);
+    };
+    (@args $cmd:ident; ..$params:expr $(, $($rest:tt)*)?) => {
+        for param in $params {
+            $cmd.push_param(param);
+        }
+        $crate::cmd!(@args $cmd; $($($rest)*)?);
+    };
+    (@args $cmd:ident; {$param:expr} $(, $($rest:tt)*)?) => {
+        $cmd.push_param($param);
+        $crate::cmd!(@args $cmd; $($($rest)*)?);
+    };
+    (@args $cmd:ident; $key:literal = $value:expr $(, $($rest:tt)*)?) => {
+        $cmd.push_option($key, $value);
+        $crate::cmd!(@args $cmd; $($($rest)*)?);
+    };
+    (@args $cmd:ident; $arg:literal $(, $($rest:tt)*)?) => {
+        $crate::macros::MacroLiteral::push_to($arg, &mut $cmd);
+        $crate::cmd!(@args $cmd; $($($rest)*)?);
+    };
+
+    ($($args:tt)+) => {{
+        let mut cmd = $crate::Cmd::new();
+        $crate::cmd!(@envs cmd; $($args)+);
+        cmd
+    }};
+}
+
+// literal argument of `cmd!`, role is chosen by the literal type and content
+#[doc(hidden)]
+pub trait MacroLiteral<'a> {
+    fn push_to(self, cmd: &mut Cmd<'a>);
+}
+
+// separator, flag or parameter (same as `Cmd::parse_shell()`)
+impl<'a> MacroLiteral<'a> for &'a str {
+    fn push_to(self, cmd: &mut Cmd<'a>) {
+        let separated = cmd
+            .args
+            .as_ref()
+            .is_some_and(|args| args.contains(&Arg::Separator));
+
+        if separated {
+            cmd.push_param(self);
+        } else if self == FLAGS_ARGS_SEPARATOR {
+            cmd.push_arg(Arg::Separator);
+        } else if self.starts_with('-') {
+            cmd.push_flag(self);
+        } else {
+            cmd.push_param(self);
+        }
+    }
+}
+
+// short flag
+impl<'a> MacroLiteral<'a> for char {
+    fn push_to(self, cmd: &mut Cmd<'a>) {
+        cmd.push_flag_short(self);
+    }
+}
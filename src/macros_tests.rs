@@ -0,0 +1,89 @@
+#[test]
+fn cmd_macro_name() {
+    use crate::{cmd, Cmd};
+
+    let cmd = cmd!("ls");
+    assert_eq!(cmd, Cmd::with_name("ls"));
+}
+
+#[test]
+fn cmd_macro_env_subcommand() {
+    use crate::cmd;
+
+    let name = String::from("tmux");
+    let session = "my session";
+    let detached = false;
+    let cmd = cmd!(
+        TMUX = "",
+        TERM = name.clone() + "-256color",
+        { name },
+        'u',
+        ["new-session", 's', "-n" = session, if detached => { 'd' }],
+    );
+    assert_eq!(
+        cmd.to_vec(),
+        vec![
+            "TMUX=",
+            "TERM=tmux-256color",
+            "tmux",
+            "-u",
+            "new-session",
+            "-s",
+            "-n",
+            "my session"
+        ]
+    );
+}
+
+#[test]
+fn cmd_macro_separator_params() {
+    use crate::{cmd, Arg};
+
+    // literals after separator and expressions are parameters
+    let param = "-x";
+    let cmd = cmd!("rm", "-f", { param }, "--", "-y", ..[String::from("-z")]);
+    assert_eq!(
+        cmd.args.unwrap(),
+        vec![
+            Arg::flag("-f"),
+            Arg::param("-x"),
+            Arg::Separator,
+            Arg::param("-y"),
+            Arg::param("-z"),
+        ]
+    );
+}
+
+#[test]
+fn cmd_macro_expression_param_hyphen() {
+    use crate::{cmd, Arg};
+
+    // expression is a parameter, but it is rendered without separator
+    let param = "-x";
+    let mut cmd = cmd!("rm", "-f", { param });
+    assert_eq!(cmd.args, Some(vec![Arg::flag("-f"), Arg::param("-x")]));
+    assert_eq!(cmd.to_string(), "rm -f -x");
+
+    cmd.auto_flags_args_separator();
+    assert_eq!(cmd.to_string(), "rm -f -- -x");
+
+    let cmd = cmd!("rm", "-f", "--", { param });
+    assert_eq!(cmd.to_string(), "rm -f -- -x");
+}
+
+#[test]
+fn cmd_macro_conditional() {
+    use crate::cmd;
+
+    let detached = true;
+    let cmd = cmd!("tmux", ["new-session", if detached => { 'd' }, "-s" = "main"]);
+    assert_eq!(cmd.to_string(), "tmux new-session -d -s main");
+
+    let detached = false;
+    let cmd = cmd!("tmux", ["new-session", if detached => { 'd' }, "-s" = "main"]);
+    assert_eq!(cmd.to_string(), "tmux new-session -s main");
+
+    let verbose = false;
+    let cmd = cmd!("make", if verbose => { "--verbose" } else { "--quiet" });
+    assert_eq!(cmd.to_string(), "make --quiet");
+}
@@ -0,0 +1,351 @@
+use super::shell::PosixLine;
+use super::stdio::{apply_stdio, Redirect};
+use super::Cmd;
+use std::borrow::Cow;
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+use std::io;
+use std::process::Command;
+
+const EMPTY_CMD: &str = "";
+const CMD_ARG_SEPARATOR: &str = " ";
+const ENV_SEPARATOR: &str = "=";
+
+/// [`Cmd`] variant holding data as [`Cow<'a, OsStr>`][std::borrow::Cow], for arguments which
+/// are not valid UTF-8 (e.g. file names, [`std::env::args_os()`])
+///
+/// Arguments have no typed roles, `OsCmd` can be created from [`Cmd`] and extended with
+/// `OsStr` arguments afterwards
+///
+/// # Examples
+/// ```
+/// use cmd_builder::{Cmd, OsCmd};
+/// use std::ffi::OsStr;
+///
+/// let mut cmd = OsCmd::from(Cmd::with_name("cp").push_flag("-a"));
+/// cmd.push_param(OsStr::new("my file")).push_param(OsStr::new("backup/"));
+/// assert_eq!(cmd.to_string(), "cp -a 'my file' backup/");
+/// ```
+#[derive(Debug, Default, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct OsCmd<'a> {
+    /// working directory of the command
+    pub current_dir: Option<Cow<'a, OsStr>>,
+
+    /// clear inherited environment variables
+    pub env_clear: bool,
+
+    /// inherited environment variables to remove
+    pub env_removes: Option<Vec<Cow<'a, OsStr>>>,
+
+    /// environment variables
+    pub envs: Option<Vec<(Cow<'a, OsStr>, Cow<'a, OsStr>)>>,
+
+    /// command name
+    pub name: Option<Cow<'a, OsStr>>,
+
+    /// arguments: flags, options, parameters, subcommands
+    pub args: Option<Vec<Cow<'a, OsStr>>>,
+
+    /// stdin configuration
+    pub stdin: Option<Redirect<'a>>,
+
+    /// stdout configuration
+    pub stdout: Option<Redirect<'a>>,
+
+    /// stderr configuration
+    pub stderr: Option<Redirect<'a>>,
+
+    /// separator between command and it's arguments (" ")
+    pub separator: Option<Cow<'a, str>>,
+}
+
+impl<'a> fmt::Display for OsCmd<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_shell_string())
+    }
+}
+
+impl<'a> OsCmd<'a> {
+    /// Create new `OsCmd` structure (using `default()` method)
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Create and set `OsCmd.name` field
+    pub fn with_name<S: Into<Cow<'a, OsStr>>>(name: S) -> Self {
+        OsCmd {
+            name: Some(name.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Set `OsCmd.name` field
+    pub fn name<S: Into<Cow<'a, OsStr>>>(&mut self, name: S) -> &mut Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Set `OsCmd.current_dir` field
+    pub fn current_dir<S: Into<Cow<'a, OsStr>>>(&mut self, dir: S) -> &mut Self {
+        self.current_dir = Some(dir.into());
+        self
+    }
+
+    /// Set `OsCmd.env_clear` to `true`, inherited environment variables will not be passed
+    pub fn env_clear(&mut self) -> &mut Self {
+        self.env_clear = true;
+        self
+    }
+
+    /// Add an inherited environment variable to remove to `OsCmd.env_removes`
+    pub fn env_remove<S: Into<Cow<'a, OsStr>>>(&mut self, key: S) -> &mut Self {
+        self.env_removes.get_or_insert(Vec::new()).push(key.into());
+        self
+    }
+
+    /// Add an environment variable to `OsCmd.envs`
+    pub fn env<T, U>(&mut self, key: T, value: U) -> &mut Self
+    where
+        T: Into<Cow<'a, OsStr>>,
+        U: Into<Cow<'a, OsStr>>,
+    {
+        self.envs
+            .get_or_insert(Vec::new())
+            .push((key.into(), value.into()));
+        self
+    }
+
+    /// push a single flag (`-x`)
+    pub fn push_flag<S: Into<Cow<'a, OsStr>>>(&mut self, flag: S) -> &mut Self {
+        self.args.get_or_insert(Vec::new()).push(flag.into());
+        self
+    }
+
+    /// push an option, flag and value (`-x  <VALUE>`)
+    pub fn push_option<U, V>(&mut self, key: U, option: V) -> &mut Self
+    where
+        U: Into<Cow<'a, OsStr>>,
+        V: Into<Cow<'a, OsStr>>,
+    {
+        self.args
+            .get_or_insert(Vec::new())
+            .extend([key.into(), option.into()]);
+        self
+    }
+
+    /// push a single parameter (`<VALUE>`)
+    pub fn push_param<S: Into<Cow<'a, OsStr>>>(&mut self, param: S) -> &mut Self {
+        self.args.get_or_insert(Vec::new()).push(param.into());
+        self
+    }
+
+    /// Set `OsCmd.stdin` field
+    pub fn stdin(&mut self, stdin: Redirect<'a>) -> &mut Self {
+        self.stdin = Some(stdin);
+        self
+    }
+
+    /// Set `OsCmd.stdout` field
+    pub fn stdout(&mut self, stdout: Redirect<'a>) -> &mut Self {
+        self.stdout = Some(stdout);
+        self
+    }
+
+    /// Set `OsCmd.stderr` field
+    pub fn stderr(&mut self, stderr: Redirect<'a>) -> &mut Self {
+        self.stderr = Some(stderr);
+        self
+    }
+
+    /// Set `OsCmd.separator` field
+    pub fn separator<S: Into<Cow<'a, str>>>(&mut self, separator: S) -> &mut Self {
+        self.separator = Some(separator.into());
+        self
+    }
+
+    /// Transform `OsCmd` to `Vec<Cow<'a, OsStr>>` (environment variables as `KEY=VALUE`,
+    /// followed by command name and arguments)
+    pub fn to_vec(&self) -> Vec<Cow<'a, OsStr>> {
+        let mut v: Vec<Cow<'a, OsStr>> = Vec::new();
+
+        if let Some(envs) = &self.envs {
+            for (key, value) in envs {
+                let mut env = key.to_os_string();
+                env.push(ENV_SEPARATOR);
+                env.push(value);
+                v.push(Cow::Owned(env));
+            }
+        }
+
+        if let Some(name) = &self.name {
+            v.push(name.clone());
+        }
+
+        if let Some(args) = &self.args {
+            v.extend(args.iter().cloned());
+        }
+
+        v
+    }
+
+    /// Transform `OsCmd` to a POSIX sh command line, every word is quoted if needed, invalid
+    /// UTF-8 bytes are escaped (see [`crate::quote::posix_quote_os()`]), rendered the same way as
+    /// [`Cmd::to_shell_string()`]
+    pub fn to_shell_string(&self) -> String {
+        PosixLine {
+            current_dir: self.current_dir.as_deref(),
+            env_clear: self.env_clear,
+            env_removes: self
+                .env_removes
+                .as_ref()
+                .map(|keys| keys.iter().map(|key| key.as_ref()).collect()),
+            envs: self
+                .envs
+                .iter()
+                .flatten()
+                .map(|(key, value)| (key.as_ref(), value.as_ref()))
+                .collect(),
+            argv: self
+                .name
+                .iter()
+                .chain(self.args.iter().flatten())
+                .map(|arg| arg.as_ref())
+                .collect(),
+            stdin: self.stdin.as_ref(),
+            stdout: self.stdout.as_ref(),
+            stderr: self.stderr.as_ref(),
+            separator: self.separator.as_deref().unwrap_or(CMD_ARG_SEPARATOR),
+        }
+        .render()
+    }
+
+    /// Transform `OsCmd` into [`std::process::Command`], arguments are passed untouched, only
+    /// stdio configurations not requiring opening files are applied (see
+    /// [`OsCmd::try_to_command()`])
+    pub fn to_command(self) -> Command {
+        Command::from(&self)
+    }
+
+    /// Transform `OsCmd` into [`std::process::Command`], applying all stdio configurations
+    /// (files are opened), in-memory stdin is piped, but not written
+    pub fn try_to_command(&self) -> io::Result<Command> {
+        let mut command = Command::from(self);
+        apply_stdio(
+            &mut command,
+            self.stdin.as_ref(),
+            self.stdout.as_ref(),
+            self.stderr.as_ref(),
+            false,
+        )?;
+        Ok(command)
+    }
+}
+
+// all strings are converted into owned `OsString`, rendering is done by `Cmd`
+impl<'a> From<&Cmd<'a>> for OsCmd<'a> {
+    fn from(cmd: &Cmd<'a>) -> Self {
+        let envs: Vec<_> = cmd
+            .to_envs()
+            .into_iter()
+            .map(|(key, value)| {
+                (
+                    Cow::Owned(OsString::from(key.into_owned())),
+                    Cow::Owned(OsString::from(value.into_owned())),
+                )
+            })
+            .collect();
+
+        let mut argv = cmd
+            .to_argv()
+            .into_iter()
+            .map(|arg| Cow::Owned(OsString::from(arg.into_owned())));
+
+        OsCmd {
+            current_dir: cmd
+                .current_dir
+                .as_ref()
+                .map(|dir| Cow::Owned(OsString::from(dir.to_string()))),
+            env_clear: cmd.env_clear,
+            env_removes: cmd.env_removes.as_ref().map(|keys| {
+                keys.iter()
+                    .map(|key| Cow::Owned(OsString::from(key.to_string())))
+                    .collect()
+            }),
+            envs: (!envs.is_empty()).then_some(envs),
+            name: argv.next(),
+            args: Some(argv.collect::<Vec<_>>()).filter(|args| !args.is_empty()),
+            stdin: cmd.stdin.clone(),
+            stdout: cmd.stdout.clone(),
+            stderr: cmd.stderr.clone(),
+            separator: cmd.separator.clone(),
+        }
+    }
+}
+
+impl<'a> From<Cmd<'a>> for OsCmd<'a> {
+    fn from(cmd: Cmd<'a>) -> Self {
+        OsCmd::from(&cmd)
+    }
+}
+
+impl<'a> From<&mut Cmd<'a>> for OsCmd<'a> {
+    fn from(cmd: &mut Cmd<'a>) -> Self {
+        OsCmd::from(&*cmd)
+    }
+}
+
+// create ready to exec [`std::process::Command`]
+// * create [`std::process::Command`]
+// * set working directory, clear or remove inherited environment variables
+// * push environment variables
+// * push binary arguments
+// * set stdio not requiring opening files
+impl<'a> From<&OsCmd<'a>> for Command {
+    fn from(cmd: &OsCmd) -> Self {
+        // user given command or blank command
+        let name = cmd.name.as_deref().unwrap_or_else(|| OsStr::new(EMPTY_CMD));
+        let mut command = Command::new(name);
+
+        // working directory
+        if let Some(dir) = &cmd.current_dir {
+            command.current_dir(dir);
+        }
+
+        // inherited environment variables
+        if cmd.env_clear {
+            command.env_clear();
+        }
+        for key in cmd.env_removes.iter().flatten() {
+            command.env_remove(key);
+        }
+
+        // environment variables
+        if let Some(envs) = &cmd.envs {
+            command.envs(envs.iter().map(|(key, value)| (key, value)));
+        }
+
+        // arguments
+        if let Some(args) = &cmd.args {
+            command.args(args);
+        }
+
+        // stdio (not requiring opening files)
+        if let Some(stdin) = cmd.stdin.as_ref().and_then(|stdin| stdin.to_stdio()) {
+            command.stdin(stdin);
+        }
+        if let Some(stdout) = cmd.stdout.as_ref().and_then(|stdout| stdout.to_stdio()) {
+            command.stdout(stdout);
+        }
+        if let Some(stderr) = cmd.stderr.as_ref().and_then(|stderr| stderr.to_stdio()) {
+            command.stderr(stderr);
+        }
+
+        command
+    }
+}
+
+impl<'a> From<OsCmd<'a>> for Command {
+    fn from(cmd: OsCmd) -> Self {
+        Command::from(&cmd)
+    }
+}
@@ -0,0 +1,187 @@
+// `NAME` environment variable and parameter are not valid UTF-8
+#[cfg(unix)]
+fn non_utf8_cmd() -> crate::OsCmd<'static> {
+    use crate::{Cmd, OsCmd};
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let file = OsStr::from_bytes(b"report \xff\xfe.txt");
+    let mut cmd = OsCmd::from(Cmd::with_name("tar").env("LANG", "C").opt("-f", "a.tar"));
+    cmd.env(OsStr::new("NAME"), file).push_param(file);
+    cmd
+}
+
+#[cfg(unix)]
+#[test]
+fn os_cmd_non_utf8_to_vec() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    assert_eq!(
+        non_utf8_cmd().to_vec(),
+        vec![
+            OsStr::new("LANG=C"),
+            OsStr::from_bytes(b"NAME=report \xff\xfe.txt"),
+            OsStr::new("tar"),
+            OsStr::new("-f"),
+            OsStr::new("a.tar"),
+            OsStr::from_bytes(b"report \xff\xfe.txt"),
+        ]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn os_cmd_non_utf8_to_string() {
+    assert_eq!(
+        non_utf8_cmd().to_string(),
+        "LANG=C NAME='report '$'\\xff\\xfe'.txt tar -f a.tar 'report '$'\\xff\\xfe'.txt"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn os_cmd_non_utf8_to_command() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let command = non_utf8_cmd().to_command();
+    assert_eq!(command.get_program(), "tar");
+    assert_eq!(
+        command.get_args().collect::<Vec<_>>(),
+        vec![
+            OsStr::new("-f"),
+            OsStr::new("a.tar"),
+            OsStr::from_bytes(b"report \xff\xfe.txt")
+        ]
+    );
+    assert_eq!(
+        command.get_envs().collect::<Vec<_>>(),
+        vec![
+            (OsStr::new("LANG"), Some(OsStr::new("C"))),
+            (
+                OsStr::new("NAME"),
+                Some(OsStr::from_bytes(b"report \xff\xfe.txt"))
+            )
+        ]
+    );
+}
+
+// `make` with working directory, environment and redirections
+fn make_cmd() -> crate::Cmd<'static> {
+    use crate::{Cmd, Redirect};
+
+    let mut cmd = Cmd::with_name("make");
+    cmd.current_dir("my dir")
+        .env_clear()
+        .env_remove("MAKEFLAGS")
+        .env("CC", "clang")
+        .param("all")
+        .stdin(Redirect::Null)
+        .stdout(Redirect::File("build.log".into()))
+        .stderr(Redirect::Stdout);
+    cmd
+}
+
+#[test]
+fn os_cmd_from_cmd() {
+    use crate::OsCmd;
+
+    let cmd = make_cmd();
+    let os_cmd = OsCmd::from(&cmd);
+    assert_eq!(os_cmd.to_string(), cmd.to_shell_string());
+    assert_eq!(
+        os_cmd.to_string(),
+        "(cd 'my dir' && env -i -u MAKEFLAGS CC=clang make all < /dev/null > build.log 2>&1)"
+    );
+}
+
+#[test]
+fn os_cmd_from_cmd_to_command() {
+    use crate::OsCmd;
+    use std::ffi::OsStr;
+
+    let command = OsCmd::from(&make_cmd()).to_command();
+    assert_eq!(command.get_current_dir(), Some("my dir".as_ref()));
+    assert_eq!(
+        command.get_envs().collect::<Vec<_>>(),
+        vec![(OsStr::new("CC"), Some(OsStr::new("clang")))]
+    );
+}
+
+#[test]
+fn os_cmd_setters() {
+    use crate::{Cmd, OsCmd, Redirect};
+    use std::ffi::OsStr;
+
+    let mut cmd = Cmd::with_name("make");
+    cmd.current_dir("src")
+        .env_clear()
+        .env_remove("MAKEFLAGS")
+        .param("all")
+        .stdin(Redirect::Null)
+        .stdout(Redirect::Append("build.log".into()))
+        .stderr(Redirect::Stdout);
+
+    let mut os_cmd = OsCmd::with_name(OsStr::new("make"));
+    os_cmd
+        .current_dir(OsStr::new("src"))
+        .env_clear()
+        .env_remove(OsStr::new("MAKEFLAGS"))
+        .push_param(OsStr::new("all"))
+        .stdin(Redirect::Null)
+        .stdout(Redirect::Append("build.log".into()))
+        .stderr(Redirect::Stdout);
+
+    assert_eq!(os_cmd, OsCmd::from(&cmd));
+    assert_eq!(os_cmd.to_string(), cmd.to_shell_string());
+}
+
+#[test]
+fn os_cmd_separator() {
+    use crate::{Cmd, OsCmd};
+
+    // separator is carried over
+    let mut cmd = Cmd::with_name("tmux");
+    cmd.push_flag("-d");
+    cmd.separator = Some(" \\\n  ".into());
+    let os_cmd = OsCmd::from(&cmd);
+    assert_eq!(os_cmd.separator.as_deref(), Some(" \\\n  "));
+    assert_eq!(os_cmd.to_string(), cmd.to_shell_string());
+}
+
+#[test]
+fn os_cmd_program_assignment() {
+    use crate::{Cmd, OsCmd};
+
+    // program looking like an assignment is quoted the same way
+    let cmd = Cmd::with_name("A=b").param("c").to_owned();
+    let os_cmd = OsCmd::from(&cmd);
+    assert_eq!(os_cmd.to_string(), "'A=b' c");
+    assert_eq!(os_cmd.to_string(), cmd.to_shell_string());
+}
+
+#[test]
+fn os_cmd_program_assignment_env() {
+    use crate::OsCmd;
+    use std::ffi::OsStr;
+
+    // `env` would take the program for an assignment
+    let mut os_cmd = OsCmd::with_name(OsStr::new("A=b c"));
+    os_cmd.env(OsStr::new("MY KEY"), OsStr::new("x"));
+    assert_eq!(
+        os_cmd.to_string(),
+        r#"env 'MY KEY=x' sh -c 'exec "$0" "$@"' 'A=b c'"#
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn os_cmd_program_non_utf8() {
+    use crate::OsCmd;
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let cmd = OsCmd::with_name(OsStr::from_bytes(b"A=\xff"));
+    assert_eq!(cmd.to_string(), "'A='$'\\xff'");
+}
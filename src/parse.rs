@@ -0,0 +1,464 @@
+use super::quote::is_posix_name;
+use std::error::Error;
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Command line parsing error
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ParseError {
+    /// single quoted string is not closed (`'...`)
+    UnterminatedSingleQuote,
+    /// double quoted string is not closed (`"...`)
+    UnterminatedDoubleQuote,
+    /// backslash at the end of the input, nothing to escape (`...\`)
+    TrailingBackslash,
+    /// braced block is not closed (`{...`)
+    UnterminatedBrace,
+    /// closing brace without opening one (`...}`)
+    UnexpectedBrace,
+    /// unquoted shell operator (`;`, `|`, `&`, `<`, `>`, `(`, `)`), lists, pipelines and
+    /// redirections can not be represented by a single command
+    UnquotedOperator(char),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnterminatedSingleQuote => write!(f, "unterminated single quote"),
+            ParseError::UnterminatedDoubleQuote => write!(f, "unterminated double quote"),
+            ParseError::TrailingBackslash => write!(f, "trailing backslash"),
+            ParseError::UnterminatedBrace => write!(f, "unterminated brace"),
+            ParseError::UnexpectedBrace => write!(f, "unexpected brace"),
+            ParseError::UnquotedOperator(c) => write!(f, "unquoted shell operator `{}`", c),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Single word of the command line, after quote removal
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub(crate) struct Word {
+    /// word text without quotes
+    pub value: String,
+    /// position (in bytes of `value`) of the first quoted or escaped character, if any
+    pub quoted_from: Option<usize>,
+}
+
+impl Word {
+    fn push(&mut self, c: char, quoted: bool) {
+        if quoted && self.quoted_from.is_none() {
+            self.quoted_from = Some(self.value.len());
+        }
+        self.value.push(c);
+    }
+
+    /// Short flags cluster without leading hyphen (`-abc` = `abc`), if word is unquoted
+    pub fn short_flags(&self) -> Option<&str> {
+        let flags = self.value.strip_prefix('-')?;
+        if self.quoted_from.is_none()
+            && !flags.is_empty()
+            && flags.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            Some(flags)
+        } else {
+            None
+        }
+    }
+
+    /// Split word into an environment variable assignment (`KEY=VALUE`), if `KEY` is a valid
+    /// unquoted name (see [`is_posix_name()`])
+    pub fn assignment(&self) -> Option<(&str, &str)> {
+        let i = self.value.find('=')?;
+        if self.quoted_from.is_some_and(|q| q <= i) {
+            return None;
+        }
+
+        let key = &self.value[..i];
+        is_posix_name(key).then(|| (key, &self.value[i + 1..]))
+    }
+}
+
+/// Split the command line into words using POSIX sh quoting rules [^f1]
+///
+/// * unquoted `<blank>` and `<newline>` characters separate words
+/// * backslash preserves the literal value of the following character, `<backslash><newline>`
+///   is removed (line continuation)
+/// * single quotes preserve the literal value of each enclosed character
+/// * double quotes preserve the literal value of each enclosed character, except backslash
+///   followed by `$`, `` ` ``, `"`, `\` or `<newline>`
+/// * dollar single quotes (`$'...'`) process backslash escapes (`\n`, `\t`, `\xHH`, `\ooo`,
+///   ...), invalid UTF-8 is replaced with `U+FFFD`
+///
+/// Parameter expansion, command substitution and globbing are not performed, corresponding
+/// characters are kept as is. Unquoted operators (`;`, `|`, `&`, `<`, `>`, `(`, `)`) are an
+/// error ([`ParseError::UnquotedOperator`])
+///
+/// [^f1] [[POSIX Shell Command Language](https://pubs.opengroup.org/onlinepubs/9699919799/utilities/V3_chap02.html#tag_02_02)]
+pub(crate) fn split_posix(s: &str) -> Result<Vec<Word>, ParseError> {
+    let mut words = Vec::new();
+    let mut word: Option<Word> = None;
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' | '\n' => {
+                if let Some(word) = word.take() {
+                    words.push(word);
+                }
+            }
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some(c) => word.get_or_insert_with(Word::default).push(c, true),
+                None => return Err(ParseError::TrailingBackslash),
+            },
+            '\'' => {
+                let word = word.get_or_insert_with(Word::default);
+                // empty quoted string is a word too
+                word.quoted_from.get_or_insert(word.value.len());
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c, true),
+                        None => return Err(ParseError::UnterminatedSingleQuote),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(Word::default);
+                word.quoted_from.get_or_insert(word.value.len());
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('\n') => {}
+                            Some(c @ ('$' | '`' | '"' | '\\')) => word.push(c, true),
+                            Some(c) => {
+                                word.push('\\', true);
+                                word.push(c, true);
+                            }
+                            None => return Err(ParseError::UnterminatedDoubleQuote),
+                        },
+                        Some(c) => word.push(c, true),
+                        None => return Err(ParseError::UnterminatedDoubleQuote),
+                    }
+                }
+            }
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                posix_dollar_quote(&mut chars, word.get_or_insert_with(Word::default))?;
+            }
+            ';' | '|' | '&' | '<' | '>' | '(' | ')' => return Err(ParseError::UnquotedOperator(c)),
+            c => word.get_or_insert_with(Word::default).push(c, false),
+        }
+    }
+
+    if let Some(word) = word {
+        words.push(word);
+    }
+
+    Ok(words)
+}
+
+// dollar single quotes (after `$'`), backslash escapes are processed
+fn posix_dollar_quote(chars: &mut Peekable<Chars>, word: &mut Word) -> Result<(), ParseError> {
+    word.quoted_from.get_or_insert(word.value.len());
+    // escapes produce bytes, not necessarily valid UTF-8
+    let mut bytes = Vec::new();
+    let mut buf = [0; 4];
+
+    while let Some(c) = chars.next() {
+        let c = match c {
+            '\'' => {
+                word.value.push_str(&String::from_utf8_lossy(&bytes));
+                return Ok(());
+            }
+            '\\' => chars.next().ok_or(ParseError::UnterminatedSingleQuote)?,
+            c => {
+                bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                continue;
+            }
+        };
+        match c {
+            // `\xHH` hexadecimal, `\ooo` octal byte
+            'x' | '0'..='7' => {
+                let (mut digits, radix, len) = match c {
+                    'x' => (String::new(), 16, 2),
+                    c => (c.to_string(), 8, 3),
+                };
+                while digits.len() < len {
+                    match chars.next_if(|c| c.is_digit(radix)) {
+                        Some(c) => digits.push(c),
+                        None => break,
+                    }
+                }
+                match u32::from_str_radix(&digits, radix) {
+                    Ok(value) => bytes.push(value as u8),
+                    // `\x` without digits is kept as is
+                    Err(_) => bytes.extend_from_slice(b"\\x"),
+                }
+            }
+            'a' => bytes.push(0x07),
+            'b' => bytes.push(0x08),
+            'e' | 'E' => bytes.push(0x1b),
+            'f' => bytes.push(0x0c),
+            'n' => bytes.push(b'\n'),
+            'r' => bytes.push(b'\r'),
+            't' => bytes.push(b'\t'),
+            'v' => bytes.push(0x0b),
+            '\\' | '\'' | '"' | '?' => bytes.push(c as u8),
+            // unknown escape is kept as is
+            c => {
+                bytes.push(b'\\');
+                bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            }
+        }
+    }
+    Err(ParseError::UnterminatedSingleQuote)
+}
+
+/// Split the tmux command sequence into commands and words using tmux parsing rules [^f1]
+///
+/// * unquoted `;` and `<newline>` terminate a command, `\;` is a literal semicolon (passed as
+///   an argument, e.g. for `bind-key`)
+/// * `#` at the beginning of a word starts a comment till the end of the line, except `#{`
+/// * `#{ ... }` format (nested formats included) is kept literally as a part of the word
+/// * `{ ... }` braced block is a single word containing the enclosed text
+/// * single quotes preserve the literal value of each enclosed character
+/// * double quotes and unquoted text process backslash escapes (`\e`, `\r`, `\n`, `\t`,
+///   `\uXXXX`, `\UXXXXXXXX`, `\ooo`) and `$VAR`, `${VAR}` environment variables
+/// * unquoted `~` at the beginning of a word is the `HOME` environment variable
+///
+/// `%if`, `%hidden` and other directives are not processed, corresponding text is kept as is
+///
+/// [^f1] [[tmux manual](https://man7.org/linux/man-pages/man1/tmux.1.html#PARSING_SYNTAX)]
+pub(crate) fn split_tmux<F>(s: &str, lookup: F) -> Result<Vec<Vec<Word>>, ParseError>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut commands = Vec::new();
+    let mut words = Vec::new();
+    let mut word: Option<Word> = None;
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' | '\r' => {
+                if let Some(word) = word.take() {
+                    words.push(word);
+                }
+            }
+            ';' | '\n' => {
+                if let Some(word) = word.take() {
+                    words.push(word);
+                }
+                if !words.is_empty() {
+                    commands.push(std::mem::take(&mut words));
+                }
+            }
+            '#' if chars.peek() == Some(&'{') => {
+                tmux_format(&mut chars, word.get_or_insert_with(Word::default))?
+            }
+            '#' if word.is_none() => while chars.next_if(|c| *c != '\n').is_some() {},
+            '{' if word.is_none() => {
+                let value = tmux_braces(&mut chars)?;
+                word = Some(Word {
+                    value,
+                    quoted_from: Some(0),
+                });
+            }
+            '}' => return Err(ParseError::UnexpectedBrace),
+            '~' if word.is_none()
+                && chars
+                    .peek()
+                    .is_none_or(|c| matches!(c, '/' | ' ' | '\t' | '\r' | '\n' | ';')) =>
+            {
+                let word = word.get_or_insert_with(Word::default);
+                for c in lookup("HOME").unwrap_or_default().chars() {
+                    word.push(c, true);
+                }
+            }
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some(c) => tmux_escape(c, &mut chars, word.get_or_insert_with(Word::default)),
+                None => return Err(ParseError::TrailingBackslash),
+            },
+            '$' => tmux_variable(&mut chars, &lookup, word.get_or_insert_with(Word::default)),
+            '\'' => {
+                let word = word.get_or_insert_with(Word::default);
+                word.quoted_from.get_or_insert(word.value.len());
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c, true),
+                        None => return Err(ParseError::UnterminatedSingleQuote),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(Word::default);
+                word.quoted_from.get_or_insert(word.value.len());
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('\n') => {}
+                            Some(c) => tmux_escape(c, &mut chars, word),
+                            None => return Err(ParseError::UnterminatedDoubleQuote),
+                        },
+                        Some('$') => tmux_variable(&mut chars, &lookup, word),
+                        Some(c) => word.push(c, true),
+                        None => return Err(ParseError::UnterminatedDoubleQuote),
+                    }
+                }
+            }
+            c => word.get_or_insert_with(Word::default).push(c, false),
+        }
+    }
+
+    if let Some(word) = word {
+        words.push(word);
+    }
+    if !words.is_empty() {
+        commands.push(words);
+    }
+
+    Ok(commands)
+}
+
+// escaped character (after backslash)
+fn tmux_escape(c: char, chars: &mut Peekable<Chars>, word: &mut Word) {
+    let c = match c {
+        'e' => '\x1b',
+        'r' => '\r',
+        'n' => '\n',
+        't' => '\t',
+        'u' | 'U' => {
+            let len = if c == 'u' { 4 } else { 8 };
+            let mut hex = String::new();
+            while hex.len() < len {
+                match chars.next_if(|c| c.is_ascii_hexdigit()) {
+                    Some(c) => hex.push(c),
+                    None => break,
+                }
+            }
+            match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                Some(c) => c,
+                None => {
+                    word.push(c, true);
+                    hex.chars().for_each(|c| word.push(c, true));
+                    return;
+                }
+            }
+        }
+        '0'..='7' => {
+            let mut oct = c.to_string();
+            while oct.len() < 3 {
+                match chars.next_if(|c| matches!(c, '0'..='7')) {
+                    Some(c) => oct.push(c),
+                    None => break,
+                }
+            }
+            u32::from_str_radix(&oct, 8)
+                .ok()
+                .and_then(char::from_u32)
+                .unwrap_or(c)
+        }
+        c => c,
+    };
+    word.push(c, true);
+}
+
+// environment variable (after dollar sign), `$VAR` or `${VAR}`
+fn tmux_variable<F>(chars: &mut Peekable<Chars>, lookup: &F, word: &mut Word)
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let braced = chars.next_if_eq(&'{').is_some();
+    let mut name = String::new();
+    while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_') {
+        name.push(c);
+    }
+
+    if braced && chars.next_if_eq(&'}').is_none() || name.is_empty() {
+        // not a variable, keep as is
+        word.push('$', true);
+        if braced {
+            word.push('{', true);
+        }
+        name.chars().for_each(|c| word.push(c, true));
+        return;
+    }
+
+    // mark word as quoted even if variable is empty
+    word.quoted_from.get_or_insert(word.value.len());
+    for c in lookup(&name).unwrap_or_default().chars() {
+        word.push(c, true);
+    }
+}
+
+// format (after `#`), `#{...}` including nested braces, kept literally
+fn tmux_format(chars: &mut Peekable<Chars>, word: &mut Word) -> Result<(), ParseError> {
+    let mut depth = 0;
+    word.push('#', true);
+    for c in chars.by_ref() {
+        word.push(c, true);
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(());
+                }
+            }
+            _ => {}
+        }
+    }
+    Err(ParseError::UnterminatedBrace)
+}
+
+// braced block (after opening brace), enclosed text without outer braces
+fn tmux_braces(chars: &mut Peekable<Chars>) -> Result<String, ParseError> {
+    let mut s = String::new();
+    let mut depth = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(s.trim().to_string());
+                }
+            }
+            '\\' => {
+                s.push(c);
+                if let Some(c) = chars.next() {
+                    s.push(c);
+                }
+                continue;
+            }
+            '\'' | '"' => {
+                s.push(c);
+                let quote = c;
+                while let Some(c) = chars.next() {
+                    s.push(c);
+                    if c == quote {
+                        break;
+                    }
+                    if c == '\\' && quote == '"' {
+                        if let Some(c) = chars.next() {
+                            s.push(c);
+                        }
+                    }
+                }
+                continue;
+            }
+            _ => {}
+        }
+        s.push(c);
+    }
+
+    Err(ParseError::UnterminatedBrace)
+}
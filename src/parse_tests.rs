@@ -0,0 +1,250 @@
+#[test]
+fn parse_shell() {
+    use crate::{Arg, Cmd};
+
+    let cmd: Cmd = "A=1 B='x y' git -a -bc \"--msg=it's\" -d 'a\\b' \"\\$HOME\" -- -e ''"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        cmd.envs,
+        Some(vec![("A".into(), "1".into()), ("B".into(), "x y".into())])
+    );
+    assert_eq!(cmd.name.as_deref(), Some("git"));
+    assert_eq!(cmd.flags_short.as_deref(), Some("abc"));
+    assert_eq!(
+        cmd.args,
+        Some(vec![
+            Arg::flag("--msg=it's"),
+            Arg::flag("-d"),
+            Arg::param("a\\b"),
+            Arg::param("$HOME"),
+            Arg::Separator,
+            Arg::param("-e"),
+            Arg::param(""),
+        ])
+    );
+}
+
+#[test]
+fn parse_shell_dollar_quote() {
+    use crate::{Arg, Cmd};
+
+    let cmd = Cmd::parse_shell(r"printf $'a\tb\n' $'it\'s' $'\x41\101\q' x$'\xff'").unwrap();
+    assert_eq!(
+        cmd.args,
+        Some(vec![
+            Arg::param("a\tb\n"),
+            Arg::param("it's"),
+            Arg::param("AA\\q"),
+            Arg::param("x\u{fffd}"),
+        ])
+    );
+}
+
+#[test]
+fn parse_shell_quoted_assignment() {
+    use crate::Cmd;
+
+    // quoted assignment is a command name
+    let cmd = Cmd::parse_shell("'A=1' cmd").unwrap();
+    assert_eq!(cmd.envs, None);
+    assert_eq!(cmd.name.as_deref(), Some("A=1"));
+}
+
+#[test]
+fn parse_shell_round_trip() {
+    use crate::Cmd;
+
+    // rendered and parsed back
+    let mut cmd = Cmd::with_name("tmux");
+    cmd.env("MSG", "it's here")
+        .push_flag_short('d')
+        .push_flag("--long")
+        .param("$HOME");
+    assert_eq!(Cmd::parse_shell(&cmd.to_string()), Ok(cmd.clone()));
+    cmd.opt("-t", "my session");
+    assert_eq!(
+        Cmd::parse_shell(&cmd.to_string()).unwrap().to_string(),
+        cmd.to_string()
+    );
+}
+
+#[test]
+fn parse_shell_unterminated() {
+    use crate::parse::ParseError;
+    use crate::Cmd;
+
+    assert_eq!(
+        Cmd::parse_shell("echo 'abc"),
+        Err(ParseError::UnterminatedSingleQuote)
+    );
+    assert_eq!(
+        Cmd::parse_shell("echo $'abc\\'"),
+        Err(ParseError::UnterminatedSingleQuote)
+    );
+    assert_eq!(
+        Cmd::parse_shell("echo \"abc"),
+        Err(ParseError::UnterminatedDoubleQuote)
+    );
+    assert_eq!(
+        Cmd::parse_shell("echo abc\\"),
+        Err(ParseError::TrailingBackslash)
+    );
+}
+
+// environment of tmux parsing tests
+fn tmux_lookup(key: &str) -> Option<String> {
+    match key {
+        "HOME" => Some("/home/user".to_string()),
+        "NAME" => Some("main".to_string()),
+        _ => None,
+    }
+}
+
+#[test]
+fn parse_tmux() {
+    use crate::CmdList;
+
+    let s = r#"
+# comment
+new-session -d -s "$NAME" -c ~/src ; set -g status-left '$NAME #S'
+bind-key x new-window \; split-window # comment
+bind-key y {
+    display "a } b" ; kill-server
+}
+display "\e[1m${NAME}é\101" $UNDEFINED
+"#;
+
+    let cmds = CmdList::parse_tmux_with_env(s, tmux_lookup).unwrap();
+    assert_eq!(
+        cmds.to_vec(),
+        vec![
+            "new-session",
+            "-ds",
+            "main",
+            "-c",
+            "/home/user/src",
+            ";",
+            "set",
+            "-g",
+            "status-left",
+            "$NAME #S",
+            ";",
+            "bind-key",
+            "x",
+            "new-window",
+            ";",
+            "split-window",
+            ";",
+            "bind-key",
+            "y",
+            "display \"a } b\" ; kill-server",
+            ";",
+            "display",
+            "\x1b[1mmain\u{e9}A",
+            ""
+        ]
+    );
+    assert_eq!(cmds.commands.len(), 5);
+    assert_eq!(cmds.commands[2].args.as_ref().unwrap().len(), 4);
+}
+
+#[test]
+fn parse_tmux_unbalanced_braces() {
+    use crate::parse::ParseError;
+    use crate::CmdList;
+
+    assert_eq!(
+        CmdList::parse_tmux_with_env("bind x { kill-server", tmux_lookup),
+        Err(ParseError::UnterminatedBrace)
+    );
+    assert_eq!(
+        CmdList::parse_tmux_with_env("bind x }", tmux_lookup),
+        Err(ParseError::UnexpectedBrace)
+    );
+}
+
+#[test]
+fn parse_tmux_formats() {
+    use crate::parse::ParseError;
+    use crate::CmdList;
+
+    // formats are kept literally, also nested and inside a word
+    let cmds = CmdList::parse_tmux_with_env(
+        "display -p #{pane_id} ; rename x#{session_name}-#{?#{pane_active},a;b,c} # comment",
+        tmux_lookup,
+    )
+    .unwrap();
+    assert_eq!(
+        cmds.to_vec(),
+        vec![
+            "display",
+            "-p",
+            "#{pane_id}",
+            ";",
+            "rename",
+            "x#{session_name}-#{?#{pane_active},a;b,c}"
+        ]
+    );
+    assert_eq!(
+        CmdList::parse_tmux_with_env("display #{pane_id", tmux_lookup),
+        Err(ParseError::UnterminatedBrace)
+    );
+}
+
+#[test]
+fn parse_shell_unquoted_operator() {
+    use crate::parse::ParseError;
+    use crate::Cmd;
+
+    assert_eq!(
+        Cmd::parse_shell("echo a; rm b"),
+        Err(ParseError::UnquotedOperator(';'))
+    );
+    assert_eq!(
+        Cmd::parse_shell("ls | wc"),
+        Err(ParseError::UnquotedOperator('|'))
+    );
+    assert_eq!(
+        Cmd::parse_shell("sleep 1 &"),
+        Err(ParseError::UnquotedOperator('&'))
+    );
+    assert_eq!(
+        Cmd::parse_shell("cat <in"),
+        Err(ParseError::UnquotedOperator('<'))
+    );
+    assert_eq!(
+        Cmd::parse_shell("echo a>out"),
+        Err(ParseError::UnquotedOperator('>'))
+    );
+    assert_eq!(
+        Cmd::parse_shell("echo (a)"),
+        Err(ParseError::UnquotedOperator('('))
+    );
+}
+
+#[test]
+fn parse_shell_quoted_operator() {
+    use crate::{Arg, Cmd};
+
+    // quoted or escaped operators are plain characters
+    let cmd = Cmd::parse_shell(r#"echo 'a;b' "c|d" e\&f $'<>'"#).unwrap();
+    assert_eq!(
+        cmd.args,
+        Some(vec![
+            Arg::param("a;b"),
+            Arg::param("c|d"),
+            Arg::param("e&f"),
+            Arg::param("<>"),
+        ])
+    );
+}
+
+#[test]
+fn parse_shell_stdin_param() {
+    use crate::{Arg, Cmd};
+
+    let cmd = Cmd::parse_shell("cat - -n").unwrap();
+    assert_eq!(cmd.flags_short, None);
+    assert_eq!(cmd.args, Some(vec![Arg::param("-"), Arg::flag("-n")]));
+}
@@ -0,0 +1,244 @@
+use super::cmd::join_stdin;
+use super::exec::{CmdError, CmdOutput};
+use super::Cmd;
+use std::borrow::Cow;
+use std::fmt;
+use std::io;
+use std::process::{Child, ExitStatus, Stdio};
+use std::thread;
+use std::time::Instant;
+
+/// pipe operator (`a | b`)
+const PIPE_SEPARATOR: &str = " | ";
+
+/// Commands connected by pipes, stdout of each command is stdin of the next one
+/// (`producer | filter | consumer`)
+///
+/// # Examples
+/// ```
+/// use cmd_builder::{Cmd, Pipeline};
+///
+/// let pipeline = Pipeline::new()
+///     .cmd(Cmd::with_name("printf").param("b\na\n").to_owned())
+///     .cmd(Cmd::with_name("sort").to_owned());
+/// assert_eq!(pipeline.to_string(), "printf 'b\na\n' | sort");
+///
+/// let output = pipeline.run().unwrap();
+/// assert_eq!(output.stdout_lossy(), "a\nb\n");
+/// ```
+#[derive(Debug, Default, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Pipeline<'a> {
+    pub commands: Vec<Cmd<'a>>,
+
+    /// pipeline status is the status of the rightmost failed command (`set -o pipefail`),
+    /// instead of the status of the last command
+    pub pipefail: bool,
+}
+
+/// Executed pipeline result, output of each command
+///
+/// stdin of the first command and stdout of the last command are applied as configured in
+/// their `Cmd`, stdout of all other commands is piped to the next command (so it is empty),
+/// stderr of all commands is captured (if not configured otherwise)
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PipelineOutput {
+    /// outputs of all commands, in the pipeline order
+    pub outputs: Vec<CmdOutput>,
+
+    /// pipefail semantics used for the pipeline status
+    pub pipefail: bool,
+}
+
+impl PipelineOutput {
+    /// Output of the command determining the pipeline status, the last command or (if pipefail
+    /// is used) the rightmost failed command, `None` if there are no outputs
+    pub fn status_output(&self) -> Option<&CmdOutput> {
+        let last = self.outputs.last()?;
+        if self.pipefail {
+            self.outputs
+                .iter()
+                .rev()
+                .find(|output| !output.success())
+                .or(Some(last))
+        } else {
+            Some(last)
+        }
+    }
+
+    /// Pipeline exit status, status of the last command or (if pipefail is used) the rightmost
+    /// failed command, success if there are no outputs (same as an empty shell command)
+    pub fn status(&self) -> ExitStatus {
+        self.status_output()
+            .map(|output| output.status)
+            .unwrap_or_default()
+    }
+
+    /// Returns `true` if the pipeline exited successfully
+    pub fn success(&self) -> bool {
+        self.status().success()
+    }
+
+    /// Captured stdout of the last command
+    pub fn stdout(&self) -> &[u8] {
+        self.outputs
+            .last()
+            .map(|output| output.stdout.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Captured stdout of the last command as string (invalid UTF-8 sequences are replaced with
+    /// `U+FFFD`)
+    pub fn stdout_lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(self.stdout())
+    }
+}
+
+impl<'a> fmt::Display for Pipeline<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_shell_string())
+    }
+}
+
+impl<'a> Pipeline<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, command: Cmd<'a>) {
+        self.commands.push(command);
+    }
+
+    pub fn cmd(mut self, command: Cmd<'a>) -> Self {
+        self.commands.push(command);
+        self
+    }
+
+    /// Set `Pipeline.pipefail` to `true`
+    pub fn pipefail(&mut self) -> &mut Self {
+        self.pipefail = true;
+        self
+    }
+
+    pub fn not_pipefail(&mut self) -> &mut Self {
+        self.pipefail = false;
+        self
+    }
+
+    pub fn pipefail_ext(&mut self, state: bool) -> &mut Self {
+        self.pipefail = state;
+        self
+    }
+
+    /// Transform `Pipeline` to a POSIX sh command line (see [`Cmd::to_shell_string()`]),
+    /// commands are joined by pipes
+    pub fn to_shell_string(&self) -> String {
+        self.commands
+            .iter()
+            .map(|cmd| cmd.to_shell_string())
+            .collect::<Vec<_>>()
+            .join(PIPE_SEPARATOR)
+    }
+
+    /// Execute all commands simultaneously, connecting stdout of each command with stdin of the
+    /// next one, failed commands are not an error
+    pub fn output(&self) -> Result<PipelineOutput, CmdError> {
+        self.output_stages().map_err(|(_, error)| CmdError::Io {
+            command: self.to_string(),
+            error,
+        })
+    }
+
+    // execute pipeline (see `output()`), error with index of the command which caused it
+    pub(crate) fn output_stages(&self) -> Result<PipelineOutput, (usize, io::Error)> {
+        if self.commands.is_empty() {
+            return Err((
+                0,
+                io::Error::new(io::ErrorKind::InvalidInput, "empty pipeline"),
+            ));
+        }
+
+        let start = Instant::now();
+        let mut children: Vec<Child> = Vec::new();
+        let mut writer = None;
+        let last = self.commands.len() - 1;
+
+        for (i, cmd) in self.commands.iter().enumerate() {
+            let spawned = cmd.to_command_stdio(true).and_then(|mut command| {
+                if let Some(stdout) = children.last_mut().and_then(|child| child.stdout.take()) {
+                    command.stdin(stdout);
+                }
+                if i < last {
+                    command.stdout(Stdio::piped());
+                }
+                command.spawn()
+            });
+
+            match spawned {
+                Ok(mut child) => {
+                    if i == 0 {
+                        writer = cmd.write_stdin(&mut child);
+                    }
+                    children.push(child);
+                }
+                Err(error) => {
+                    // already started commands are not needed anymore
+                    for mut child in children {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                    }
+                    // stdin writer ends as the first command is gone, its result does not matter
+                    let _ = join_stdin(writer.take());
+                    return Err((i, error));
+                }
+            }
+        }
+
+        // all commands are waited simultaneously, otherwise pipes may be filled up
+        let outputs = thread::scope(|scope| {
+            let handles: Vec<_> = children
+                .into_iter()
+                .map(|child| {
+                    scope.spawn(move || {
+                        child
+                            .wait_with_output()
+                            .map(|output| CmdOutput::new(output, start.elapsed()))
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .enumerate()
+                .map(|(i, handle)| {
+                    handle
+                        .join()
+                        .expect("pipeline wait thread panicked")
+                        .map_err(|error| (i, error))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        });
+
+        // in-memory stdin is written to the first command, writer is joined on error too
+        let written = join_stdin(writer);
+        let outputs = outputs?;
+        written.map_err(|error| (0, error))?;
+
+        Ok(PipelineOutput {
+            outputs,
+            pipefail: self.pipefail,
+        })
+    }
+
+    /// Execute pipeline (see [`Pipeline::output()`]), failed pipeline status is an error
+    /// ([`CmdError::Failed`] containing the output of the command determining the status)
+    pub fn run(&self) -> Result<PipelineOutput, CmdError> {
+        let output = self.output()?;
+        match output.status_output() {
+            Some(failed) if !failed.success() => Err(CmdError::Failed {
+                command: self.to_string(),
+                output: failed.clone(),
+            }),
+            _ => Ok(output),
+        }
+    }
+}
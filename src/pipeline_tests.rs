@@ -0,0 +1,114 @@
+// `cat | sort | head -n 2` with in-memory input
+fn sort_pipeline() -> crate::Pipeline<'static> {
+    use crate::{Cmd, Pipeline, Redirect};
+
+    Pipeline::new()
+        .cmd(
+            Cmd::with_name("cat")
+                .stdin(Redirect::bytes(&b"b\nc\na\n"[..]))
+                .to_owned(),
+        )
+        .cmd(Cmd::with_name("sort").to_owned())
+        .cmd(Cmd::with_name("head").opt("-n", "2").to_owned())
+}
+
+// sorting pipeline followed by a failing command and `true`
+fn failing_pipeline() -> crate::Pipeline<'static> {
+    use crate::Cmd;
+
+    let mut pipeline = sort_pipeline();
+    pipeline.push(
+        Cmd::with_name("sh")
+            .opt("-c", "cat >/dev/null; echo oops >&2; exit 3")
+            .to_owned(),
+    );
+    pipeline.push(Cmd::with_name("true"));
+    pipeline
+}
+
+#[test]
+fn pipeline_to_string() {
+    assert_eq!(
+        sort_pipeline().to_string(),
+        r"printf 'b\nc\na\n' | cat | sort | head -n 2"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn pipeline_run() {
+    let output = sort_pipeline().run().unwrap();
+    assert_eq!(output.outputs.len(), 3);
+    assert_eq!(output.stdout_lossy(), "a\nb\n");
+    assert!(output.outputs[0].stdout.is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn pipeline_last_status() {
+    // status of the last command
+    let output = failing_pipeline().output().unwrap();
+    assert!(output.success());
+    assert_eq!(output.outputs[3].code(), Some(3));
+}
+
+#[cfg(unix)]
+#[test]
+fn pipeline_pipefail() {
+    // status of the rightmost failed command
+    let mut pipeline = failing_pipeline();
+    pipeline.pipefail();
+    let output = pipeline.output().unwrap();
+    assert_eq!(output.status().code(), Some(3));
+    let err = pipeline.run().unwrap_err();
+    assert_eq!(err.code(), Some(3));
+    assert_eq!(err.stderr().as_deref(), Some("oops\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn pipeline_missing_binary() {
+    use crate::{Cmd, Pipeline};
+
+    let pipeline = Pipeline::new()
+        .cmd(Cmd::with_name("yes").to_owned())
+        .cmd(Cmd::with_name("cmd-builder-missing-binary").to_owned());
+    assert!(pipeline.output().is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn pipeline_empty() {
+    use crate::{Pipeline, PipelineOutput};
+
+    assert!(Pipeline::new().output().is_err());
+    let output = PipelineOutput {
+        outputs: Vec::new(),
+        pipefail: true,
+    };
+    assert!(output.status_output().is_none());
+    assert!(output.success());
+}
+
+#[cfg(unix)]
+#[test]
+fn pipeline_missing_stage_with_stdin() {
+    use crate::{Cmd, CmdError, Pipeline, Redirect};
+    use std::io;
+
+    // stdin writer is blocked on the full pipe until the first command is killed
+    let input = vec![b'x'; 4 << 20];
+    let pipeline = Pipeline::new()
+        .cmd(
+            Cmd::with_name("cat")
+                .stdin(Redirect::bytes(input))
+                .to_owned(),
+        )
+        .cmd(Cmd::with_name("cat").to_owned())
+        .cmd(Cmd::with_name("cmd-builder-missing-binary").to_owned());
+
+    match pipeline.output() {
+        Err(CmdError::Io { error, .. }) => assert_eq!(error.kind(), io::ErrorKind::NotFound),
+        other => panic!("unexpected output {:?}", other.map(|output| output.outputs)),
+    }
+}
@@ -0,0 +1,420 @@
+use std::borrow::Cow;
+use std::ffi::OsStr;
+
+/// POSIX sh single quote
+const SINGLE_QUOTE: char = '\'';
+/// POSIX sh single quote inside already single quoted word (close, escape, reopen)
+const SINGLE_QUOTE_ESCAPED: &str = "'\\''";
+/// POSIX sh empty word
+const EMPTY_WORD: &str = "''";
+
+/// Characters which have no special meaning for POSIX sh and can be left unquoted [^f1]
+///
+/// [^f1] "...The application shall quote the following characters if they are to represent
+/// themselves: `| & ; < > ( ) $ ` \ " '` `<space> <tab> <newline>` and the following may need to
+/// be quoted under certain circumstances: `* ? [ # ~ = %`..."
+/// [[POSIX Shell Command Language](https://pubs.opengroup.org/onlinepubs/9699919799/utilities/V3_chap02.html#tag_02_02)]
+fn is_posix_safe(c: char) -> bool {
+    c.is_ascii_alphanumeric()
+        || matches!(c, '@' | '%' | '+' | '=' | ':' | ',' | '.' | '/' | '-' | '_')
+}
+
+/// Quote a single word for POSIX sh
+///
+/// Words containing only safe characters are returned unchanged (borrowed), all other words are
+/// wrapped in single quotes, embedded single quotes are escaped as `'\''`
+///
+/// # Examples
+/// ```
+/// use cmd_builder::quote::posix_quote;
+///
+/// assert_eq!(posix_quote("file.txt"), "file.txt");
+/// assert_eq!(posix_quote("my file; rm -rf ~"), "'my file; rm -rf ~'");
+/// assert_eq!(posix_quote("it's"), "'it'\\''s'");
+/// assert_eq!(posix_quote(""), "''");
+/// ```
+pub fn posix_quote(word: &str) -> Cow<'_, str> {
+    if word.is_empty() {
+        return Cow::Borrowed(EMPTY_WORD);
+    }
+
+    if word.chars().all(is_posix_safe) {
+        return Cow::Borrowed(word);
+    }
+
+    let mut s = String::with_capacity(word.len() + 2);
+    s.push(SINGLE_QUOTE);
+    for c in word.chars() {
+        if c == SINGLE_QUOTE {
+            s.push_str(SINGLE_QUOTE_ESCAPED);
+        } else {
+            s.push(c);
+        }
+    }
+    s.push(SINGLE_QUOTE);
+    Cow::Owned(s)
+}
+
+/// Returns `true` if `s` is a valid POSIX sh variable name [^f1]
+///
+/// [^f1] "...In the shell command language, a word consisting solely of underscores, digits,
+/// and alphabetics from the portable character set. The first character of a name is not a
+/// digit..."
+/// [[POSIX Base Definitions](https://pubs.opengroup.org/onlinepubs/9699919799/basedefs/V1_chap03.html#tag_03_235)]
+pub fn is_posix_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Quote the first word of a command (program) for POSIX sh, same as [`posix_quote()`], but
+/// `NAME=...` words are quoted too, otherwise the shell would read them as an assignment
+///
+/// # Examples
+/// ```
+/// use cmd_builder::quote::posix_quote_program;
+///
+/// assert_eq!(posix_quote_program("ls"), "ls");
+/// assert_eq!(posix_quote_program("A=b"), "'A=b'");
+/// assert_eq!(posix_quote_program("--a=b"), "--a=b");
+/// ```
+pub fn posix_quote_program(word: &str) -> Cow<'_, str> {
+    let assignment = word
+        .split_once('=')
+        .is_some_and(|(key, _)| is_posix_name(key));
+    match posix_quote(word) {
+        Cow::Borrowed(word) if assignment => {
+            Cow::Owned(format!("{}{}{}", SINGLE_QUOTE, word, SINGLE_QUOTE))
+        }
+        quoted => quoted,
+    }
+}
+
+/// Quote an environment variable assignment for POSIX sh (`KEY=VALUE`)
+///
+/// Only the value is quoted, the key must stay unquoted, otherwise the shell will not recognize
+/// the word as an assignment. Key which is not a valid name (see [`is_posix_name()`]) can not be
+/// assigned by the shell, the whole word is quoted then, usable only as an argument of `env`
+///
+/// # Examples
+/// ```
+/// use cmd_builder::quote::posix_quote_env;
+///
+/// assert_eq!(posix_quote_env("LANGUAGE", "en_US.UTF-8"), "LANGUAGE=en_US.UTF-8");
+/// assert_eq!(posix_quote_env("MSG", "hello world"), "MSG='hello world'");
+/// assert_eq!(posix_quote_env("MY KEY", "x"), "'MY KEY=x'");
+/// ```
+pub fn posix_quote_env(key: &str, value: &str) -> String {
+    if is_posix_name(key) {
+        format!("{}={}", key, posix_quote(value))
+    } else {
+        posix_quote(&format!("{}={}", key, value)).into_owned()
+    }
+}
+
+/// Quote a single word containing arbitrary bytes for POSIX sh
+///
+/// Valid UTF-8 parts are quoted using [`posix_quote()`], invalid bytes are escaped using
+/// dollar single quotes (`$'\xFF'`), adjacent parts are concatenated into one word. On non-unix
+/// platforms the word is converted lossily (invalid sequences are replaced with `U+FFFD`)
+///
+/// # Examples
+/// ```
+/// use cmd_builder::quote::posix_quote_os;
+/// use std::ffi::OsStr;
+///
+/// assert_eq!(posix_quote_os(OsStr::new("my file")), "'my file'");
+/// #[cfg(unix)]
+/// {
+///     use std::os::unix::ffi::OsStrExt;
+///     let word = OsStr::from_bytes(b"file\xff.txt");
+///     assert_eq!(posix_quote_os(word), "file$'\\xff'.txt");
+/// }
+/// ```
+pub fn posix_quote_os(word: &OsStr) -> Cow<'_, str> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        let bytes = word.as_bytes();
+        if let Ok(word) = std::str::from_utf8(bytes) {
+            return posix_quote(word);
+        }
+
+        let mut s = String::new();
+        // consecutive invalid bytes, escaped together
+        let mut invalid = String::new();
+        for chunk in bytes.utf8_chunks() {
+            if !chunk.valid().is_empty() {
+                if !invalid.is_empty() {
+                    s.push_str(&format!("$'{}'", invalid));
+                    invalid.clear();
+                }
+                s.push_str(&posix_quote(chunk.valid()));
+            }
+            for b in chunk.invalid() {
+                invalid.push_str(&format!("\\x{:02x}", b));
+            }
+        }
+        if !invalid.is_empty() {
+            s.push_str(&format!("$'{}'", invalid));
+        }
+        Cow::Owned(s)
+    }
+
+    #[cfg(not(unix))]
+    {
+        match word.to_string_lossy() {
+            Cow::Borrowed(word) => posix_quote(word),
+            Cow::Owned(word) => Cow::Owned(posix_quote(&word).into_owned()),
+        }
+    }
+}
+
+// word which can be quoted for POSIX sh, shared rendering of `Cmd` (`str`) and `OsCmd` (`OsStr`)
+pub(crate) trait PosixWord {
+    // quoted word (see `posix_quote()`)
+    fn quote(&self) -> Cow<'_, str>;
+    // quoted program name (see `posix_quote_program()`)
+    fn quote_program(&self) -> Cow<'_, str>;
+    // quoted assignment (see `posix_quote_env()`)
+    fn quote_env(key: &Self, value: &Self) -> String;
+    // valid shell variable name (see `is_posix_name()`)
+    fn is_name(&self) -> bool;
+    // contains `=`, read as an assignment by `env`
+    fn has_equals_sign(&self) -> bool;
+}
+
+impl PosixWord for str {
+    fn quote(&self) -> Cow<'_, str> {
+        posix_quote(self)
+    }
+
+    fn quote_program(&self) -> Cow<'_, str> {
+        posix_quote_program(self)
+    }
+
+    fn quote_env(key: &Self, value: &Self) -> String {
+        posix_quote_env(key, value)
+    }
+
+    fn is_name(&self) -> bool {
+        is_posix_name(self)
+    }
+
+    fn has_equals_sign(&self) -> bool {
+        self.contains('=')
+    }
+}
+
+impl PosixWord for OsStr {
+    fn quote(&self) -> Cow<'_, str> {
+        posix_quote_os(self)
+    }
+
+    // same as `str` for valid UTF-8, otherwise the leading `NAME=` is quoted
+    fn quote_program(&self) -> Cow<'_, str> {
+        if let Some(word) = self.to_str() {
+            return posix_quote_program(word);
+        }
+        let quoted = posix_quote_os(self);
+        match quoted.split_once('=') {
+            Some((key, value)) if is_posix_name(key) => {
+                Cow::Owned(format!("{0}{1}={0}{2}", SINGLE_QUOTE, key, value))
+            }
+            _ => quoted,
+        }
+    }
+
+    fn quote_env(key: &Self, value: &Self) -> String {
+        match (key.to_str(), value.to_str()) {
+            (Some(key), Some(value)) => posix_quote_env(key, value),
+            _ if key.is_name() => format!("{}={}", posix_quote_os(key), posix_quote_os(value)),
+            _ => {
+                let mut env = key.to_os_string();
+                env.push("=");
+                env.push(value);
+                posix_quote_os(&env).into_owned()
+            }
+        }
+    }
+
+    fn is_name(&self) -> bool {
+        self.to_str().is_some_and(is_posix_name)
+    }
+
+    fn has_equals_sign(&self) -> bool {
+        self.as_encoded_bytes().contains(&b'=')
+    }
+}
+
+/// Characters which have no special meaning for fish and can be left unquoted
+fn is_fish_safe(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '@' | '+' | '=' | ':' | ',' | '.' | '/' | '-' | '_')
+}
+
+/// Quote a single word for fish
+///
+/// Words containing only safe characters are returned unchanged (borrowed), all other words are
+/// wrapped in single quotes, embedded single quotes and backslashes are escaped with backslash
+///
+/// # Examples
+/// ```
+/// use cmd_builder::quote::fish_quote;
+///
+/// assert_eq!(fish_quote("file.txt"), "file.txt");
+/// assert_eq!(fish_quote("it's $HOME"), "'it\\'s $HOME'");
+/// assert_eq!(fish_quote("a\\b"), "'a\\\\b'");
+/// ```
+pub fn fish_quote(word: &str) -> Cow<'_, str> {
+    if word.is_empty() {
+        return Cow::Borrowed(EMPTY_WORD);
+    }
+    if word.chars().all(is_fish_safe) {
+        return Cow::Borrowed(word);
+    }
+
+    let mut s = String::with_capacity(word.len() + 2);
+    s.push(SINGLE_QUOTE);
+    for c in word.chars() {
+        if c == SINGLE_QUOTE || c == '\\' {
+            s.push('\\');
+        }
+        s.push(c);
+    }
+    s.push(SINGLE_QUOTE);
+    Cow::Owned(s)
+}
+
+/// Windows command line double quote
+const DOUBLE_QUOTE: char = '"';
+/// Windows command line escape character (only before double quotes)
+const BACKSLASH: char = '\\';
+/// cmd.exe escape character
+const CARET: char = '^';
+
+/// Quote a single argument for a Windows command line, parsed by `CommandLineToArgvW` or the
+/// MSVC C runtime [^f1]
+///
+/// Arguments without whitespace and double quotes are returned unchanged (borrowed), all other
+/// arguments are wrapped in double quotes, backslashes followed by a double quote (embedded or
+/// the closing one) are doubled, embedded double quotes are escaped with backslash
+///
+/// [^f1] "...Backslashes are interpreted literally, unless they immediately precede a double
+/// quotation mark..."
+/// [[Parsing C++ command-line arguments](https://learn.microsoft.com/en-us/cpp/cpp/main-function-command-line-args#parsing-c-command-line-arguments)]
+///
+/// # Examples
+/// ```
+/// use cmd_builder::quote::windows_quote;
+///
+/// assert_eq!(windows_quote(r"C:\dir\file.txt"), r"C:\dir\file.txt");
+/// assert_eq!(windows_quote(r"C:\my dir\"), r#""C:\my dir\\""#);
+/// assert_eq!(windows_quote(r#"say "hi""#), r#""say \"hi\"""#);
+/// assert_eq!(windows_quote(""), r#""""#);
+/// ```
+pub fn windows_quote(word: &str) -> Cow<'_, str> {
+    if !word.is_empty() && !word.contains([' ', '\t', '\n', '\x0b', DOUBLE_QUOTE]) {
+        return Cow::Borrowed(word);
+    }
+
+    let mut s = String::with_capacity(word.len() + 2);
+    s.push(DOUBLE_QUOTE);
+    let mut backslashes = 0;
+    for c in word.chars() {
+        match c {
+            BACKSLASH => backslashes += 1,
+            DOUBLE_QUOTE => {
+                // escape all backslashes and the quote itself
+                s.extend(std::iter::repeat_n(BACKSLASH, backslashes * 2 + 1));
+                s.push(c);
+                backslashes = 0;
+            }
+            _ => {
+                s.extend(std::iter::repeat_n(BACKSLASH, backslashes));
+                s.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+    // backslashes before the closing quote
+    s.extend(std::iter::repeat_n(BACKSLASH, backslashes * 2));
+    s.push(DOUBLE_QUOTE);
+    Cow::Owned(s)
+}
+
+/// Escape a command line for cmd.exe, every metacharacter (`( ) % ! ^ " < > & |`) is prefixed
+/// with caret
+///
+/// Double quotes are escaped too, so cmd.exe never enters quoted mode (where caret is literal)
+/// and removes all carets before passing the line to the program. Escaping `%` prevents
+/// variable expansion on the command line (`cmd /c`), in batch files `%` must be doubled
+/// (`%%`) instead
+///
+/// # Examples
+/// ```
+/// use cmd_builder::quote::cmd_exe_escape;
+///
+/// assert_eq!(cmd_exe_escape("dir"), "dir");
+/// assert_eq!(cmd_exe_escape(r#"echo "a & b" %PATH%"#), r#"echo ^"a ^& b^" ^%PATH^%"#);
+/// ```
+pub fn cmd_exe_escape(line: &str) -> Cow<'_, str> {
+    let is_meta = |c| matches!(c, '(' | ')' | '%' | '!' | '^' | '"' | '<' | '>' | '&' | '|');
+    if !line.contains(is_meta) {
+        return Cow::Borrowed(line);
+    }
+
+    let mut s = String::with_capacity(line.len() * 2);
+    for c in line.chars() {
+        if is_meta(c) {
+            s.push(CARET);
+        }
+        s.push(c);
+    }
+    Cow::Owned(s)
+}
+
+/// Characters which have no special meaning for PowerShell and can be left unquoted
+fn is_powershell_safe(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '+' | '=' | ':' | '.' | '/' | '\\' | '-' | '_')
+}
+
+/// Quote a single word for PowerShell
+///
+/// Words containing only safe characters are returned unchanged (borrowed), all other words
+/// (including the stop-parsing token `--%`) are wrapped in single quotes, embedded single quotes
+/// (also typographic ones, which PowerShell treats the same way) are doubled. Words starting
+/// with hyphen and containing dot are quoted too, PowerShell before 7.3 splits them at the dot
+/// (`-foo.bar` is passed as `-foo` `.bar`)
+///
+/// # Examples
+/// ```
+/// use cmd_builder::quote::powershell_quote;
+///
+/// assert_eq!(powershell_quote(r"C:\dir\file.txt"), r"C:\dir\file.txt");
+/// assert_eq!(powershell_quote("it's $HOME"), "'it''s $HOME'");
+/// assert_eq!(powershell_quote("--%"), "'--%'");
+/// assert_eq!(powershell_quote("-Dfile.encoding=UTF-8"), "'-Dfile.encoding=UTF-8'");
+/// ```
+pub fn powershell_quote(word: &str) -> Cow<'_, str> {
+    if word.is_empty() {
+        return Cow::Borrowed(EMPTY_WORD);
+    }
+    let split = word.starts_with('-') && word.contains('.');
+    if !split && word.chars().all(is_powershell_safe) {
+        return Cow::Borrowed(word);
+    }
+
+    let mut s = String::with_capacity(word.len() + 2);
+    s.push(SINGLE_QUOTE);
+    for c in word.chars() {
+        if matches!(c, '\'' | '\u{2018}' | '\u{2019}' | '\u{201a}' | '\u{201b}') {
+            s.push(c);
+        }
+        s.push(c);
+    }
+    s.push(SINGLE_QUOTE);
+    Cow::Owned(s)
+}
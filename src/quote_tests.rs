@@ -0,0 +1,202 @@
+// tmux with environment, quoted parameters and subcommands
+fn tmux_cmd() -> crate::Cmd<'static> {
+    use crate::Cmd;
+
+    let mut cmd = Cmd::with_name("tmux");
+    cmd.env("MSG", "it's here")
+        .opt("-t", "my session")
+        .param("$HOME")
+        .push_cmd(Cmd::with_name("list-commands"))
+        .push_cmd(Cmd::with_name("kill-server"));
+    cmd
+}
+
+#[test]
+fn shell_quote_argv() {
+    // argv is not quoted
+    assert_eq!(
+        tmux_cmd().to_vec(),
+        vec![
+            "MSG=it's here",
+            "tmux",
+            "-t",
+            "my session",
+            "$HOME",
+            "list-commands",
+            ";",
+            "kill-server"
+        ]
+    );
+}
+
+#[test]
+fn shell_quote() {
+    // subcommands separator is an argument for the command itself, so it is quoted too
+    assert_eq!(
+        tmux_cmd().to_string(),
+        "MSG='it'\\''s here' tmux -t 'my session' '$HOME' list-commands ';' kill-server"
+    );
+}
+
+#[test]
+fn shell_quote_cmd_list() {
+    use crate::{Cmd, CmdList};
+
+    let cmds = CmdList::new()
+        .cmd(Cmd::with_name("echo").param("a;b").to_owned())
+        .cmd(Cmd::with_name("echo").param("").to_owned());
+    assert_eq!(cmds.to_string(), "echo 'a;b' ; echo ''");
+}
+
+// port of the MSVC C runtime command line parser (arguments after the program name), used to
+// check that quoted arguments are parsed back unchanged
+fn windows_split(line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| *c == ' ' || *c == '\t').is_some() {}
+        if chars.peek().is_none() {
+            return args;
+        }
+
+        let mut arg = String::new();
+        let mut quoted = false;
+        while let Some(&c) = chars.peek() {
+            match c {
+                '\\' => {
+                    let mut backslashes = 0;
+                    while chars.next_if_eq(&'\\').is_some() {
+                        backslashes += 1;
+                    }
+                    if chars.peek() == Some(&'"') {
+                        arg.extend(std::iter::repeat_n('\\', backslashes / 2));
+                        if backslashes % 2 == 1 {
+                            arg.push('"');
+                            chars.next();
+                        }
+                    } else {
+                        arg.extend(std::iter::repeat_n('\\', backslashes));
+                    }
+                }
+                '"' => {
+                    chars.next();
+                    // `""` inside quotes is a literal quote
+                    if quoted && chars.next_if_eq(&'"').is_some() {
+                        arg.push('"');
+                    } else {
+                        quoted = !quoted;
+                    }
+                }
+                ' ' | '\t' if !quoted => break,
+                _ => {
+                    arg.push(c);
+                    chars.next();
+                }
+            }
+        }
+        args.push(arg);
+    }
+}
+
+// cmd.exe caret removal (outside of quotes, quotes are always escaped)
+fn cmd_exe_unescape(line: &str) -> String {
+    let mut s = String::new();
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '^' => s.extend(chars.next()),
+            _ => s.push(c),
+        }
+    }
+    s
+}
+
+// words with spaces, quotes, backslashes and cmd.exe special characters
+const WINDOWS_WORDS: [&str; 13] = [
+    "",
+    "plain",
+    "two words",
+    "tab\there",
+    r"C:\Program Files\",
+    r"trailing\\",
+    r#"say "hi""#,
+    r#"\"already escaped\""#,
+    r#"back\\"quote"#,
+    r#"""#,
+    r"\\server\share\file",
+    "100% & ^caret | <in> (x) !v!",
+    "unicode ÿ ✓",
+];
+
+// `prog.exe` with all the Windows test words as parameters
+fn windows_cmd() -> crate::Cmd<'static> {
+    use crate::Cmd;
+
+    let mut cmd = Cmd::with_name("prog.exe");
+    for word in WINDOWS_WORDS {
+        cmd.param(word);
+    }
+    cmd
+}
+
+#[test]
+fn windows_quote_round_trip() {
+    use crate::quote::windows_quote;
+
+    let quoted: Vec<_> = WINDOWS_WORDS
+        .iter()
+        .map(|word| windows_quote(word))
+        .collect();
+    assert_eq!(windows_split(&quoted.join(" ")), WINDOWS_WORDS);
+}
+
+#[test]
+fn cmd_exe_escape_round_trip() {
+    use crate::quote::{cmd_exe_escape, windows_quote};
+
+    let quoted: Vec<_> = WINDOWS_WORDS
+        .iter()
+        .map(|word| windows_quote(word))
+        .collect();
+    let line = cmd_exe_escape(&quoted.join(" ")).into_owned();
+    assert_eq!(windows_split(&cmd_exe_unescape(&line)), WINDOWS_WORDS);
+}
+
+#[test]
+fn windows_string_round_trip() {
+    let mut argv = windows_split(&windows_cmd().to_windows_string());
+    assert_eq!(argv.remove(0), "prog.exe");
+    assert_eq!(argv, WINDOWS_WORDS);
+}
+
+#[test]
+fn cmd_exe_string_round_trip() {
+    let mut argv = windows_split(&cmd_exe_unescape(&windows_cmd().to_cmd_exe_string()));
+    assert_eq!(argv.remove(0), "prog.exe");
+    assert_eq!(argv, WINDOWS_WORDS);
+}
+
+#[test]
+fn assignment_program() {
+    use crate::Cmd;
+
+    // program looking like an assignment is quoted, parsed back as the program
+    let mut cmd = Cmd::with_name("A=b");
+    cmd.env("C", "d").param("e=f");
+    let s = cmd.to_shell_string();
+    assert_eq!(s, "C=d 'A=b' e=f");
+    let parsed = Cmd::parse_shell(&s).unwrap();
+    assert_eq!(parsed.name.as_deref(), Some("A=b"));
+    assert_eq!(parsed.to_envs(), vec![("C".into(), "d".into())]);
+}
+
+#[test]
+fn env_invalid_name() {
+    use crate::Cmd;
+
+    // variable with invalid name is set by `env`
+    let mut cmd = Cmd::with_name("printenv");
+    cmd.env("MY KEY", "x y").env("OK", "1");
+    assert_eq!(cmd.to_shell_string(), "env 'MY KEY=x y' OK=1 printenv");
+}
@@ -0,0 +1,151 @@
+//! POSIX sh script generation from [`CmdList`]
+use super::cmd_list::RunPolicy;
+use super::quote::posix_quote;
+use super::{Cmd, CmdList, Separator};
+use std::borrow::Cow;
+
+const SHEBANG: &str = "#!/bin/sh";
+
+/// Options of [`CmdList::to_script()`]
+///
+/// ```text
+/// shebang     - first line of the script (default `#!/bin/sh`)
+/// errexit     - `set -e`, exit on the first failed command (default `true`)
+/// nounset     - `set -u`, expanding unset variable is an error (default `true`)
+/// current_dir - `cd DIR` before all commands
+/// echo        - print each command line to stderr before running it (`+ cmd`)
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ScriptOptions<'a> {
+    pub shebang: Cow<'a, str>,
+    pub errexit: bool,
+    pub nounset: bool,
+    pub current_dir: Option<Cow<'a, str>>,
+    pub echo: bool,
+}
+
+impl<'a> Default for ScriptOptions<'a> {
+    fn default() -> Self {
+        ScriptOptions {
+            shebang: Cow::Borrowed(SHEBANG),
+            errexit: true,
+            nounset: true,
+            current_dir: None,
+            echo: false,
+        }
+    }
+}
+
+impl<'a> ScriptOptions<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set `ScriptOptions.shebang` field
+    pub fn shebang<S: Into<Cow<'a, str>>>(mut self, shebang: S) -> Self {
+        self.shebang = shebang.into();
+        self
+    }
+
+    /// Set `ScriptOptions.errexit` field
+    pub fn errexit(mut self, errexit: bool) -> Self {
+        self.errexit = errexit;
+        self
+    }
+
+    /// Set `ScriptOptions.nounset` field
+    pub fn nounset(mut self, nounset: bool) -> Self {
+        self.nounset = nounset;
+        self
+    }
+
+    /// Set `ScriptOptions.current_dir` field
+    pub fn current_dir<S: Into<Cow<'a, str>>>(mut self, dir: S) -> Self {
+        self.current_dir = Some(dir.into());
+        self
+    }
+
+    /// Set `ScriptOptions.echo` field
+    pub fn echo(mut self, echo: bool) -> Self {
+        self.echo = echo;
+        self
+    }
+
+    // `set -eu` line
+    fn set_line(&self) -> Option<String> {
+        let mut flags = String::new();
+        if self.errexit {
+            flags.push('e');
+        }
+        if self.nounset {
+            flags.push('u');
+        }
+        (!flags.is_empty()).then(|| format!("set -{}", flags))
+    }
+}
+
+impl<'a> CmdList<'a> {
+    /// Transform `CmdList` to a POSIX sh script
+    ///
+    /// Commands are quoted (see [`Cmd::to_shell_string()`]), including environment variables
+    /// of each command, working directory of a command is changed in a subshell. Separators
+    /// are mapped by their semantics (see [`Separator::run_policy()`]): commands executed always
+    /// are written on separate lines, `&&`, `||` and `|` sequences are written on a single
+    /// line. The script ends with a newline.
+    ///
+    /// # Examples
+    /// ```
+    /// use cmd_builder::{Cmd, CmdList, ScriptOptions, Separator};
+    ///
+    /// let mut cmds = CmdList::new()
+    ///     .cmd(Cmd::with_name("make").env("CC", "clang").to_owned())
+    ///     .cmd(Cmd::with_name("make").param("install").to_owned());
+    ///
+    /// let options = ScriptOptions::new().current_dir("/src/my project");
+    /// assert_eq!(
+    ///     cmds.to_script(&options),
+    ///     "#!/bin/sh\nset -eu\ncd '/src/my project'\nCC=clang make\nmake install\n"
+    /// );
+    ///
+    /// cmds.separator(Separator::And);
+    /// let options = ScriptOptions::new().errexit(false).nounset(false).echo(true);
+    /// assert_eq!(
+    ///     cmds.to_script(&options),
+    ///     "#!/bin/sh\n\
+    ///      printf '+ %s\\n' 'CC=clang make && make install' >&2\n\
+    ///      CC=clang make && make install\n"
+    /// );
+    /// ```
+    pub fn to_script(&self, options: &ScriptOptions) -> String {
+        let mut lines: Vec<String> = vec![options.shebang.to_string()];
+
+        lines.extend(options.set_line());
+        if let Some(dir) = &options.current_dir {
+            lines.push(format!("cd {}", posix_quote(dir)));
+        }
+
+        let policy = self
+            .separator
+            .as_ref()
+            .map(Separator::run_policy)
+            .unwrap_or_default();
+        let commands = self.commands.iter().map(Cmd::to_shell_string);
+        let commands: Vec<String> = match policy {
+            RunPolicy::Always => commands.collect(),
+            RunPolicy::OnSuccess => vec![commands.collect::<Vec<_>>().join(" && ")],
+            RunPolicy::OnFailure => vec![commands.collect::<Vec<_>>().join(" || ")],
+            RunPolicy::Pipe => vec![commands.collect::<Vec<_>>().join(" | ")],
+        };
+
+        for command in commands.into_iter().filter(|command| !command.is_empty()) {
+            if options.echo {
+                lines.push(format!("printf '+ %s\\n' {} >&2", posix_quote(&command)));
+            }
+            lines.push(command);
+        }
+
+        let mut script = lines.join("\n");
+        script.push('\n');
+        script
+    }
+}
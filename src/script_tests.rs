@@ -0,0 +1,139 @@
+// `echo`, `pwd` in `/tmp`, `false` and `echo` after the failure
+fn script_cmds() -> crate::CmdList<'static> {
+    use crate::{Cmd, CmdList};
+
+    CmdList::new()
+        .cmd(Cmd::with_name("echo").param("it's $HOME").to_owned())
+        .cmd(Cmd::with_name("pwd").current_dir("/tmp").to_owned())
+        .cmd(Cmd::with_name("false"))
+        .cmd(Cmd::with_name("echo").param("unreachable").to_owned())
+}
+
+#[test]
+fn script() {
+    use crate::ScriptOptions;
+
+    let options = ScriptOptions::new().current_dir("/");
+    assert_eq!(
+        script_cmds().to_script(&options),
+        "#!/bin/sh\n\
+         set -eu\n\
+         cd /\n\
+         echo 'it'\\''s $HOME'\n\
+         (cd /tmp && pwd)\n\
+         false\n\
+         echo unreachable\n"
+    );
+}
+
+#[test]
+fn script_options() {
+    use crate::{ScriptOptions, Separator};
+
+    let mut cmds = script_cmds();
+    cmds.separator(Separator::Or);
+    let options = ScriptOptions::new()
+        .shebang("#!/usr/bin/env bash")
+        .nounset(false)
+        .echo(true);
+    assert_eq!(
+        cmds.to_script(&options),
+        "#!/usr/bin/env bash\n\
+         set -e\n\
+         printf '+ %s\\n' 'echo '\\''it'\\''\\'\\'''\\''s $HOME'\\'' || (cd /tmp && pwd) || false || echo unreachable' >&2\n\
+         echo 'it'\\''s $HOME' || (cd /tmp && pwd) || false || echo unreachable\n"
+    );
+}
+
+#[test]
+fn script_no_options() {
+    use crate::ScriptOptions;
+
+    let options = ScriptOptions::new().errexit(false).nounset(false);
+    assert_eq!(
+        script_cmds().to_script(&options),
+        "#!/bin/sh\n\
+         echo 'it'\\''s $HOME'\n\
+         (cd /tmp && pwd)\n\
+         false\n\
+         echo unreachable\n"
+    );
+}
+
+// script commands with `pwd` in the script directory before `false`
+#[cfg(unix)]
+fn script_run_cmds() -> crate::CmdList<'static> {
+    use crate::{Cmd, CmdList};
+
+    CmdList::new()
+        .cmd(Cmd::with_name("echo").param("it's $HOME").to_owned())
+        .cmd(Cmd::with_name("pwd").current_dir("/tmp").to_owned())
+        .cmd(Cmd::with_name("pwd"))
+        .cmd(Cmd::with_name("false"))
+        .cmd(Cmd::with_name("echo").param("unreachable").to_owned())
+}
+
+// runs the script with `sh -c`
+#[cfg(unix)]
+fn run_script(cmds: &crate::CmdList, options: &crate::ScriptOptions) -> crate::CmdOutput {
+    use crate::Cmd;
+
+    Cmd::with_name("sh")
+        .opt("-c", cmds.to_script(options))
+        .output()
+        .unwrap()
+}
+
+#[cfg(unix)]
+#[test]
+fn script_run_errexit() {
+    use crate::ScriptOptions;
+
+    // errexit stops at `false`, `cd` in subshell does not change the script directory
+    let output = run_script(
+        &script_run_cmds(),
+        &ScriptOptions::new().current_dir("/").echo(true),
+    );
+    assert_eq!(output.code(), Some(1));
+    assert_eq!(output.stdout_lossy(), "it's $HOME\n/tmp\n/\n");
+    assert_eq!(
+        output.stderr_lossy(),
+        "+ echo 'it'\\''s $HOME'\n+ (cd /tmp && pwd)\n+ pwd\n+ false\n"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn script_run_no_errexit() {
+    use crate::ScriptOptions;
+
+    let output = run_script(&script_run_cmds(), &ScriptOptions::new().errexit(false));
+    assert!(output.success());
+    assert!(output.stdout_lossy().ends_with("unreachable\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn script_run_and() {
+    use crate::{ScriptOptions, Separator};
+
+    let mut cmds = script_run_cmds();
+    cmds.separator(Separator::And);
+    let output = run_script(&cmds, &ScriptOptions::new().current_dir("/"));
+    assert!(!output.success());
+    assert_eq!(output.stdout_lossy(), "it's $HOME\n/tmp\n/\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn script_run_pipe() {
+    use crate::{Cmd, CmdList, ScriptOptions, Separator};
+
+    let cmds = CmdList::new()
+        .cmd(Cmd::with_name("printf").param("b\\na\\n").to_owned())
+        .cmd(Cmd::with_name("sort"))
+        .separator(Separator::Pipe)
+        .to_owned();
+    let output = run_script(&cmds, &ScriptOptions::new());
+    assert_eq!(output.stdout_lossy(), "a\nb\n");
+}
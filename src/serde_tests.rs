@@ -0,0 +1,143 @@
+// git with environment, every argument kind, redirections and a subcommand
+fn git_cmd() -> crate::Cmd<'static> {
+    use crate::{Arg, Cmd, OptionStyle, Redirect};
+
+    let mut cmd = Cmd::with_name("git");
+    cmd.env("LANG", "C")
+        .push_flag_short('v')
+        .push_arg(Arg::flag("--all"))
+        .push_option_ext("--format", "short", OptionStyle::Equals)
+        .push_arg(Arg::Separator)
+        .push_param("a b")
+        .stdin(Redirect::Null)
+        .stdout(Redirect::file("out.log"))
+        .push_cmd(Cmd::with_name("status"));
+    cmd
+}
+
+#[test]
+fn serde_json_schema() {
+    let json = serde_json::to_value(git_cmd()).unwrap();
+    assert_eq!(json["envs"], serde_json::json!([["LANG", "C"]]));
+    assert_eq!(json["name"], "git");
+    assert_eq!(json["flags_short"], "v");
+    assert_eq!(
+        json["args"],
+        serde_json::json!([
+            {"flag": "--all"},
+            {"option": {"key": "--format", "value": "short", "style": "equals"}},
+            "separator",
+            {"param": "a b"},
+        ])
+    );
+    assert_eq!(json["subcommands"]["commands"][0]["name"], "status");
+    assert_eq!(json["subcommands"]["separator"], "semicolon");
+    assert_eq!(json["combine_short_flags"], true);
+    assert_eq!(json["option_style"], "separate");
+    assert_eq!(json["stdin"], "null");
+    assert_eq!(json["stdout"], serde_json::json!({"file": "out.log"}));
+}
+
+#[test]
+fn serde_json_round_trip() {
+    use crate::Cmd;
+
+    let cmd = git_cmd();
+    let de: Cmd = serde_json::from_value(serde_json::to_value(&cmd).unwrap()).unwrap();
+    assert_eq!(de, cmd);
+}
+
+#[test]
+fn serde_json_defaults() {
+    use crate::Cmd;
+
+    // missing fields are set to `Default` values
+    let cmd: Cmd = serde_json::from_str(r#"{"name": "ls", "args": [{"param": "-l"}]}"#).unwrap();
+    assert_eq!(cmd, Cmd::with_name("ls").param("-l").to_owned());
+    assert!(cmd.combine_short_flags && cmd.use_alias);
+}
+
+#[test]
+fn serde_json_cmd_list_separator() {
+    use crate::{CmdList, Separator};
+
+    let cmds: CmdList = serde_json::from_str(
+        r#"{"commands": [{"name": "a"}, {"name": "b"}], "separator": {"custom": "&"}}"#,
+    )
+    .unwrap();
+    assert_eq!(cmds.get_separator(), Some(&Separator::Custom("&".into())));
+    assert_eq!(cmds.to_string(), "a & b");
+
+    // missing separator is the default one
+    let cmds: CmdList = serde_json::from_str(r#"{"commands": [{"name": "a"}]}"#).unwrap();
+    assert_eq!(cmds.get_separator(), Some(&Separator::Semicolon));
+}
+
+// `make` build and install jobs
+const MAKE_TOML: &str = r#"
+    separator = "and"
+
+    [[commands]]
+    name = "make"
+    envs = [["CC", "clang"]]
+    current_dir = "build"
+    args = [{ option = { key = "-j", value = "4" } }, { param = "all" }]
+
+    [[commands]]
+    name = "make"
+    args = [{ param = "install" }]
+    stdin = { bytes = [121, 10] }
+"#;
+
+#[test]
+fn serde_toml() {
+    use crate::{CmdList, Redirect, Separator};
+
+    let cmds: CmdList = toml::from_str(MAKE_TOML).unwrap();
+    assert_eq!(cmds.get_separator(), Some(&Separator::And));
+    assert_eq!(
+        cmds.to_string(),
+        r"(cd build && CC=clang make -j 4 all) && printf 'y\n' | make install"
+    );
+    assert_eq!(cmds.commands[1].stdin, Some(Redirect::bytes(&b"y\n"[..])));
+}
+
+#[test]
+fn serde_toml_round_trip() {
+    use crate::CmdList;
+
+    let cmds: CmdList = toml::from_str(MAKE_TOML).unwrap();
+    let de: CmdList = toml::from_str(&toml::to_string(&cmds).unwrap()).unwrap();
+    assert_eq!(de, cmds);
+}
+
+#[test]
+fn serde_toml_flags_short() {
+    use crate::Cmd;
+
+    let cmd: Cmd = toml::from_str("name = \"ls\"\nflags_short = \"la\"").unwrap();
+    assert_eq!(cmd.to_string(), "ls -la");
+}
+
+#[test]
+fn serde_owned() {
+    use crate::CmdList;
+    use serde::de::DeserializeOwned;
+
+    fn load<T: DeserializeOwned>(reader: impl std::io::Read) -> T {
+        serde_json::from_reader(reader).unwrap()
+    }
+
+    // job file read from a stream, result does not borrow from the input
+    let json = br#"{"commands": [{"name": "make", "args": [{"param": "all"}]}]}"#;
+    let cmds: CmdList<'static> = load(&json[..]);
+    assert_eq!(cmds.to_string(), "make all");
+}
+
+#[test]
+fn serde_owned_cmd() {
+    use crate::Cmd;
+
+    let cmd: Cmd<'static> = serde_json::from_reader(&br#"{"name": "ls"}"#[..]).unwrap();
+    assert_eq!(cmd, Cmd::with_name("ls"));
+}
@@ -0,0 +1,534 @@
+//! Rendering of [`Cmd`], [`CmdList`] for a target shell ([`Shell`])
+use super::quote::{
+    cmd_exe_escape, fish_quote, is_posix_name, posix_quote, powershell_quote, windows_quote,
+    PosixWord,
+};
+use super::stdio::Redirect;
+use super::{Cmd, CmdList, Separator};
+use std::borrow::Cow;
+use std::fmt;
+
+/// null device of cmd.exe
+const NUL: &str = "NUL";
+/// null value of PowerShell, used as null device
+const PS_NULL: &str = "$null";
+/// change directory command (rendering of `current_dir`)
+const CD_CMD: &str = "cd";
+/// run the next command only if the previous one succeeded (`cd DIR && ...`)
+const AND_SEPARATOR: &str = "&&";
+/// environment modifying command (rendering of `env_clear`, `env_removes`)
+const ENV_CMD: &str = "env";
+/// `env` flag, start with an empty environment
+const ENV_CLEAR_FLAG: &str = "-i";
+/// `env` flag, remove variable from the environment
+const ENV_REMOVE_FLAG: &str = "-u";
+/// pipe in-memory stdin into the command (`printf ... | cmd`)
+const PIPE_SEPARATOR: &str = "|";
+/// runs program given as the next word, `env` reads any word containing `=` as an assignment,
+/// so such a program is started by `sh` (`env -i sh -c '...' A=b args...`), same in POSIX sh and
+/// fish
+const ENV_EXEC: &str = r#"sh -c 'exec "$0" "$@"'"#;
+
+// POSIX sh command line of `Cmd` (`str`) or `OsCmd` (`OsStr`), both are rendered the same way
+pub(crate) struct PosixLine<'r, S: PosixWord + ?Sized> {
+    pub(crate) current_dir: Option<&'r S>,
+    pub(crate) env_clear: bool,
+    pub(crate) env_removes: Option<Vec<&'r S>>,
+    pub(crate) envs: Vec<(&'r S, &'r S)>,
+    pub(crate) argv: Vec<&'r S>,
+    pub(crate) stdin: Option<&'r Redirect<'r>>,
+    pub(crate) stdout: Option<&'r Redirect<'r>>,
+    pub(crate) stderr: Option<&'r Redirect<'r>>,
+    pub(crate) separator: &'r str,
+}
+
+impl<'r, S: PosixWord + ?Sized> PosixLine<'r, S> {
+    // `(cd DIR && printf STDIN | env -i -u KEY KEY=value program args... <stdin >stdout 2>stderr)`
+    pub(crate) fn render(&self) -> String {
+        let mut v: Vec<Cow<str>> = Vec::new();
+
+        if let Some(dir) = self.current_dir {
+            v.extend([
+                Cow::Borrowed(CD_CMD),
+                dir.quote(),
+                Cow::Borrowed(AND_SEPARATOR),
+            ]);
+        }
+
+        // in-memory stdin, written by `printf`
+        if let Some(printf) = self.stdin.and_then(Redirect::to_printf_string) {
+            v.extend([Cow::Owned(printf), Cow::Borrowed(PIPE_SEPARATOR)]);
+        }
+
+        // variables with invalid names can be set by `env` only
+        let env = self.env_clear
+            || self.env_removes.is_some()
+            || self.envs.iter().any(|(key, _)| !key.is_name());
+        if env {
+            v.push(Cow::Borrowed(ENV_CMD));
+            if self.env_clear {
+                v.push(Cow::Borrowed(ENV_CLEAR_FLAG));
+            }
+            for key in self.env_removes.iter().flatten() {
+                v.extend([Cow::Borrowed(ENV_REMOVE_FLAG), key.quote()]);
+            }
+        }
+
+        for (key, value) in &self.envs {
+            v.push(Cow::Owned(S::quote_env(key, value)));
+        }
+
+        if let Some((program, args)) = self.argv.split_first() {
+            if env && program.has_equals_sign() {
+                v.extend([Cow::Borrowed(ENV_EXEC), program.quote()]);
+            } else {
+                v.push(program.quote_program());
+            }
+            v.extend(args.iter().map(|arg| arg.quote()));
+        }
+
+        let redirects = [
+            self.stdin.and_then(|stdin| stdin.to_stdin_string()),
+            self.stdout.and_then(|stdout| stdout.to_output_string(1)),
+            self.stderr.and_then(|stderr| stderr.to_output_string(2)),
+        ];
+        v.extend(redirects.into_iter().flatten().map(Cow::Owned));
+
+        let s = v.join(self.separator);
+        // subshell, so `cd` does not affect commands joined by `;`, `&&`, `||`, `|`
+        match self.current_dir {
+            Some(_) => format!("({})", s),
+            None => s,
+        }
+    }
+}
+
+/// Target shell of [`Cmd::render()`], [`CmdList::render()`]
+///
+/// ```text
+/// Posix      - POSIX sh (bash, zsh, dash, ...), same as `to_shell_string()`
+/// Fish       - fish (`env -C DIR VAR=value cmd`, `; and`, `; or`)
+/// PowerShell - PowerShell 7 (`& { ... ${env:VAR}='value'; cmd ... }`, `&&`, `||` require 7+)
+/// CmdExe     - cmd.exe (`cmd /d /c set VAR=value^&^& cmd`, `&` instead of `;`)
+/// ```
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Shell {
+    #[default]
+    Posix,
+    Fish,
+    PowerShell,
+    CmdExe,
+}
+
+impl Shell {
+    /// Quote a single word for the shell
+    ///
+    /// # Examples
+    /// ```
+    /// use cmd_builder::Shell;
+    ///
+    /// assert_eq!(Shell::Posix.quote("a b"), "'a b'");
+    /// assert_eq!(Shell::Fish.quote("it's"), r"'it\'s'");
+    /// assert_eq!(Shell::PowerShell.quote("it's"), "'it''s'");
+    /// assert_eq!(Shell::CmdExe.quote("a&b c"), r#"^"a^&b c^""#);
+    /// ```
+    pub fn quote<'w>(&self, word: &'w str) -> Cow<'w, str> {
+        match self {
+            Shell::Posix => posix_quote(word),
+            Shell::Fish => fish_quote(word),
+            Shell::PowerShell => powershell_quote(word),
+            Shell::CmdExe => match windows_quote(word) {
+                Cow::Borrowed(word) => cmd_exe_escape(word),
+                Cow::Owned(word) => Cow::Owned(cmd_exe_escape(&word).into_owned()),
+            },
+        }
+    }
+
+    // spelling of the separator between two commands
+    fn join_string(&self, separator: &Separator) -> Cow<'static, str> {
+        let separator = match (self, separator) {
+            (_, Separator::Newline) => return Cow::Borrowed("\n"),
+            (Shell::Fish, Separator::And) => return Cow::Borrowed("; and "),
+            (Shell::Fish, Separator::Or) => return Cow::Borrowed("; or "),
+            (Shell::PowerShell, Separator::EscapedSemicolon) => "`;",
+            (Shell::CmdExe, Separator::Semicolon) => "&",
+            (Shell::CmdExe, Separator::EscapedSemicolon) => "^;",
+            (_, separator) => separator.as_str(),
+        };
+        Cow::Owned(format!(" {} ", separator))
+    }
+}
+
+impl fmt::Display for Shell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Shell::Posix => "sh",
+            Shell::Fish => "fish",
+            Shell::PowerShell => "pwsh",
+            Shell::CmdExe => "cmd",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// cmd.exe redirection target, double quoted if needed (`"` and `^` are not allowed in paths),
+// quotes do not prevent variable expansion, so `%` and `!` are escaped outside of them
+fn cmd_exe_path(path: &str) -> Cow<'_, str> {
+    if !path.is_empty() && !path.contains([' ', '\t', '&', '|', '<', '>', '(', ')', '^', '%', '!'])
+    {
+        return Cow::Borrowed(path);
+    }
+
+    let mut s = String::with_capacity(path.len() + 2);
+    s.push('"');
+    for c in path.chars() {
+        match c {
+            '%' | '!' => s.push_str(&format!("\"^{}\"", c)),
+            c => s.push(c),
+        }
+    }
+    s.push('"');
+    Cow::Owned(s)
+}
+
+// PowerShell environment variable (`${env:KEY}`), backtick escapes characters of the braced
+// name
+fn powershell_env(key: &str) -> String {
+    let mut s = String::from("${env:");
+    for c in key.chars() {
+        if matches!(c, '`' | '{' | '}') {
+            s.push('`');
+        }
+        s.push(c);
+    }
+    s.push('}');
+    s
+}
+
+// program word is read as a variable assignment (`NAME=value`) by the shell
+fn is_assignment(word: &str) -> bool {
+    word.split_once('=')
+        .is_some_and(|(key, _)| is_posix_name(key))
+}
+
+// PowerShell, cmd.exe stdout, stderr redirection
+fn output_string(redirect: &Redirect, fd: u8, shell: Shell) -> Option<String> {
+    let fd = if fd == 1 { "" } else { "2" };
+    let (null, quote): (&str, fn(&str) -> Cow<'_, str>) = match shell {
+        Shell::CmdExe => (NUL, cmd_exe_path),
+        _ => (PS_NULL, powershell_quote),
+    };
+    match redirect {
+        Redirect::Null => Some(format!("{}> {}", fd, null)),
+        Redirect::File(path) => Some(format!("{}> {}", fd, quote(path))),
+        Redirect::Append(path) => Some(format!("{}>> {}", fd, quote(path))),
+        Redirect::Stdout if !fd.is_empty() => Some(format!("{}>&1", fd)),
+        _ => None,
+    }
+}
+
+impl<'a> Cmd<'a> {
+    /// Render `Cmd` as a command line for the given shell
+    ///
+    /// Working directory and environment apply only to the command, the rendered command is a
+    /// single unit in lists and pipelines:
+    /// ```text
+    /// Posix      - (cd DIR && env -i -u OLD VAR=value cmd)
+    /// Fish       - env -C DIR -i -u OLD VAR=value cmd
+    /// PowerShell - & { $saved = ${env:OLD}, ${env:VAR}; Push-Location -LiteralPath DIR; try {
+    ///              Remove-Item -LiteralPath Env:OLD -ErrorAction Ignore; ${env:VAR} = 'value';
+    ///              cmd } finally { Pop-Location; ${env:OLD}, ${env:VAR} = $saved } }
+    /// CmdExe     - cmd /d /c cd /d DIR^&^& set OLD=^&^& set VAR=value^&^& cmd
+    /// ```
+    ///
+    /// Fish uses `env -C` (GNU coreutils 8.28+). PowerShell has no per-command environment,
+    /// the working directory and variables are restored after the command, clearing of the
+    /// environment is not rendered. cmd.exe runs the command in a child `cmd`. Stdin from file
+    /// is piped in PowerShell (`Get-Content -LiteralPath FILE | cmd`), in-memory stdin is
+    /// written by `printf` in POSIX sh and fish (`printf 'data' | cmd`), it is not rendered for
+    /// PowerShell and cmd.exe.
+    ///
+    /// # Examples
+    /// ```
+    /// use cmd_builder::{Cmd, Redirect, Shell};
+    ///
+    /// let mut cmd = Cmd::with_name("grep");
+    /// cmd.env("LANG", "C").param("it's").stdout(Redirect::Null);
+    ///
+    /// assert_eq!(cmd.render(Shell::Posix), r"LANG=C grep 'it'\''s' > /dev/null");
+    /// assert_eq!(cmd.render(Shell::Fish), r"env LANG=C grep 'it\'s' > /dev/null");
+    /// assert_eq!(
+    ///     cmd.render(Shell::PowerShell),
+    ///     "& { $saved = ${env:LANG}; try { ${env:LANG} = 'C'; grep 'it''s' > $null } \
+    ///      finally { ${env:LANG} = $saved } }"
+    /// );
+    /// assert_eq!(
+    ///     cmd.render(Shell::CmdExe),
+    ///     "cmd /d /c set LANG=C^&^& grep it's ^> NUL"
+    /// );
+    /// ```
+    pub fn render(&self, shell: Shell) -> String {
+        self.render_piped(shell, false)
+    }
+
+    // `piped` = stdin is connected to the previous command of a pipeline
+    fn render_piped(&self, shell: Shell, piped: bool) -> String {
+        match shell {
+            Shell::Posix => self.to_shell_string(),
+            Shell::Fish => self.to_fish_string(),
+            Shell::PowerShell => self.to_powershell_string(piped),
+            Shell::CmdExe => self.to_cmd_exe_line(),
+        }
+    }
+
+    fn to_fish_string(&self) -> String {
+        let mut v: Vec<Cow<str>> = Vec::new();
+
+        // in-memory stdin, written by `printf`
+        if let Some(printf) = self
+            .stdin
+            .as_ref()
+            .and_then(|stdin| stdin.to_printf_string_with(fish_quote))
+        {
+            v.extend([Cow::Owned(printf), Cow::Borrowed(PIPE_SEPARATOR)]);
+        }
+
+        let envs = self.to_envs();
+        let env = self.current_dir.is_some()
+            || self.env_clear
+            || self.env_removes.is_some()
+            || !envs.is_empty();
+        if env {
+            v.push(Cow::Borrowed("env"));
+            if let Some(dir) = &self.current_dir {
+                v.extend([Cow::Borrowed("-C"), fish_quote(dir)]);
+            }
+            if self.env_clear {
+                v.push(Cow::Borrowed("-i"));
+            }
+            for key in self.env_removes.iter().flatten() {
+                v.extend([Cow::Borrowed("-u"), fish_quote(key)]);
+            }
+            for (key, value) in &envs {
+                v.push(Cow::Owned(format!(
+                    "{}={}",
+                    fish_quote(key),
+                    fish_quote(value)
+                )));
+            }
+        }
+
+        let argv = self.to_argv();
+        if let Some((program, args)) = argv.split_first() {
+            // fish reads unquoted `NAME=value` as an assignment, `env` any word with `=`
+            if env && program.contains('=') {
+                v.push(Cow::Borrowed(ENV_EXEC));
+            }
+            v.push(match fish_quote(program) {
+                Cow::Borrowed(program) if !env && is_assignment(program) => {
+                    Cow::Owned(format!("'{}'", program))
+                }
+                program => program,
+            });
+            v.extend(args.iter().map(|arg| fish_quote(arg)));
+        }
+
+        let redirects = [
+            self.stdin
+                .as_ref()
+                .and_then(|stdin| stdin.to_stdin_string_with(fish_quote)),
+            self.stdout
+                .as_ref()
+                .and_then(|stdout| stdout.to_output_string_with(1, fish_quote)),
+            self.stderr
+                .as_ref()
+                .and_then(|stderr| stderr.to_output_string_with(2, fish_quote)),
+        ];
+        v.extend(redirects.into_iter().flatten().map(Cow::Owned));
+
+        v.join(" ")
+    }
+
+    fn to_powershell_string(&self, piped: bool) -> String {
+        let envs = self.to_envs();
+        let keys: Vec<String> = self
+            .env_removes
+            .iter()
+            .flatten()
+            .chain(envs.iter().map(|(key, _)| key))
+            .map(|key| powershell_env(key))
+            .collect();
+        let grouped = self.current_dir.is_some() || !keys.is_empty();
+
+        let mut statements: Vec<String> = Vec::new();
+        for key in self.env_removes.iter().flatten() {
+            statements.push(format!(
+                "Remove-Item -LiteralPath {} -ErrorAction Ignore",
+                powershell_quote(&format!("Env:{}", key))
+            ));
+        }
+        for (key, value) in &envs {
+            // assignment value is an expression, bare words would be run as commands
+            let value = match powershell_quote(value) {
+                Cow::Borrowed(value) => format!("'{}'", value),
+                Cow::Owned(value) => value,
+            };
+            statements.push(format!("{} = {}", powershell_env(key), value));
+        }
+
+        let mut s = String::new();
+        match &self.stdin {
+            Some(Redirect::Null) => s.push_str("$null | "),
+            Some(Redirect::File(path)) | Some(Redirect::Append(path)) => s.push_str(&format!(
+                "Get-Content -LiteralPath {} | ",
+                powershell_quote(path)
+            )),
+            // script block passes the pipeline input on explicitly
+            None if grouped && piped => s.push_str("$input | "),
+            _ => {}
+        }
+
+        let mut v: Vec<Cow<str>> = Vec::new();
+        let argv = self.to_argv();
+        if let Some(program) = argv.first() {
+            let program = powershell_quote(program);
+            // quoted string is an expression, call operator runs it as a command
+            if program.starts_with('\'') {
+                v.push(Cow::Borrowed("&"));
+            }
+            v.push(program);
+        }
+        v.extend(argv.iter().skip(1).map(|arg| powershell_quote(arg)));
+
+        let redirects = [
+            self.stdout
+                .as_ref()
+                .and_then(|stdout| output_string(stdout, 1, Shell::PowerShell)),
+            self.stderr
+                .as_ref()
+                .and_then(|stderr| output_string(stderr, 2, Shell::PowerShell)),
+        ];
+        v.extend(redirects.into_iter().flatten().map(Cow::Owned));
+        s.push_str(&v.join(" "));
+
+        if !grouped {
+            return s;
+        }
+        statements.push(s);
+
+        // working directory and variables are restored after the command
+        let mut setup: Vec<String> = Vec::new();
+        let mut restore: Vec<String> = Vec::new();
+        if !keys.is_empty() {
+            setup.push(format!("$saved = {}", keys.join(", ")));
+            restore.push(format!("{} = $saved", keys.join(", ")));
+        }
+        if let Some(dir) = &self.current_dir {
+            setup.push(format!(
+                "Push-Location -LiteralPath {}",
+                powershell_quote(dir)
+            ));
+            restore.insert(0, String::from("Pop-Location"));
+        }
+        format!(
+            "& {{ {}; try {{ {} }} finally {{ {} }} }}",
+            setup.join("; "),
+            statements.join("; "),
+            restore.join("; ")
+        )
+    }
+
+    fn to_cmd_exe_line(&self) -> String {
+        let mut s = String::new();
+
+        if let Some(dir) = &self.current_dir {
+            s.push_str(&format!("cd /d {}&& ", cmd_exe_path(dir)));
+        }
+        for key in self.env_removes.iter().flatten() {
+            s.push_str(&format!("set {}=&& ", cmd_exe_escape(key)));
+        }
+        let envs = self.to_envs();
+        for (key, value) in &envs {
+            s.push_str(&format!(
+                "set {}={}&& ",
+                cmd_exe_escape(key),
+                cmd_exe_escape(value)
+            ));
+        }
+        let grouped = !s.is_empty();
+
+        s.push_str(&self.to_cmd_exe_string());
+
+        let redirects = [
+            self.stdin.as_ref().and_then(|stdin| match stdin {
+                Redirect::Null => Some(format!("< {}", NUL)),
+                Redirect::File(path) | Redirect::Append(path) => {
+                    Some(format!("< {}", cmd_exe_path(path)))
+                }
+                _ => None,
+            }),
+            self.stdout
+                .as_ref()
+                .and_then(|stdout| output_string(stdout, 1, Shell::CmdExe)),
+            self.stderr
+                .as_ref()
+                .and_then(|stderr| output_string(stderr, 2, Shell::CmdExe)),
+        ];
+        for redirect in redirects.into_iter().flatten() {
+            s.push(' ');
+            s.push_str(&redirect);
+        }
+
+        // child `cmd` keeps the working directory and variables for the command only, the line
+        // is escaped once more for the parent
+        if grouped {
+            s = format!("cmd /d /c {}", cmd_exe_escape(&s));
+        }
+        s
+    }
+}
+
+impl<'a> CmdList<'a> {
+    /// Render `CmdList` as a command line for the given shell, commands are rendered using
+    /// [`Cmd::render()`], separators are spelled in the shell syntax:
+    ///
+    /// ```text
+    /// Separator         Posix  Fish    PowerShell  CmdExe
+    /// Semicolon         ;      ;       ;           &
+    /// EscapedSemicolon  \;     \;      `;          ^;
+    /// And               &&     ; and   &&          &&
+    /// Or                ||     ; or    ||          ||
+    /// Pipe              |      |       |           |
+    /// ```
+    ///
+    /// Newline and custom separators are the same for all shells.
+    ///
+    /// # Examples
+    /// ```
+    /// use cmd_builder::{Cmd, CmdList, Separator, Shell};
+    ///
+    /// let mut list = CmdList::new()
+    ///     .cmd(Cmd::with_name("make"))
+    ///     .cmd(Cmd::with_name("make").param("install").to_owned());
+    /// list.separator(Separator::And);
+    ///
+    /// assert_eq!(list.render(Shell::Posix), "make && make install");
+    /// assert_eq!(list.render(Shell::Fish), "make; and make install");
+    /// ```
+    pub fn render(&self, shell: Shell) -> String {
+        let separator = match &self.separator {
+            Some(separator) => shell.join_string(separator),
+            None => Cow::Borrowed(" "),
+        };
+
+        let pipe = self.separator == Some(Separator::Pipe);
+        self.commands
+            .iter()
+            .enumerate()
+            .map(|(i, cmd)| cmd.render_piped(shell, pipe && i > 0))
+            .collect::<Vec<_>>()
+            .join(&separator)
+    }
+}
@@ -0,0 +1,313 @@
+// tool with working directory, environment, quoted arguments and redirections
+fn tool_cmd() -> crate::Cmd<'static> {
+    use crate::{Cmd, Redirect};
+
+    let mut cmd = Cmd::with_name("my tool");
+    cmd.current_dir("/tmp/a b")
+        .env("MSG", "it's")
+        .env_remove("OLD")
+        .opt("-o", "out file")
+        .param("50%")
+        .stdin(Redirect::file("in.txt"))
+        .stdout(Redirect::append("log file"))
+        .stderr(Redirect::Stdout);
+    cmd
+}
+
+#[test]
+fn render_cmd_posix() {
+    use crate::Shell;
+
+    let cmd = tool_cmd();
+    assert_eq!(
+        cmd.render(Shell::Posix),
+        "(cd '/tmp/a b' && env -u OLD MSG='it'\\''s' 'my tool' -o 'out file' 50% < in.txt \
+         >> 'log file' 2>&1)"
+    );
+    assert_eq!(cmd.render(Shell::Posix), cmd.to_shell_string());
+}
+
+#[test]
+fn render_cmd_fish() {
+    use crate::Shell;
+
+    assert_eq!(
+        tool_cmd().render(Shell::Fish),
+        "env -C '/tmp/a b' -u OLD MSG='it\\'s' 'my tool' -o 'out file' '50%' < in.txt \
+         >> 'log file' 2>&1"
+    );
+}
+
+#[test]
+fn render_cmd_powershell() {
+    use crate::Shell;
+
+    assert_eq!(
+        tool_cmd().render(Shell::PowerShell),
+        "& { $saved = ${env:OLD}, ${env:MSG}; Push-Location -LiteralPath '/tmp/a b'; try { \
+         Remove-Item -LiteralPath Env:OLD -ErrorAction Ignore; ${env:MSG} = 'it''s'; \
+         Get-Content -LiteralPath in.txt | & 'my tool' -o 'out file' '50%' >> 'log file' 2>&1 } \
+         finally { Pop-Location; ${env:OLD}, ${env:MSG} = $saved } }"
+    );
+}
+
+#[test]
+fn render_cmd_cmd_exe() {
+    use crate::Shell;
+
+    assert_eq!(
+        tool_cmd().render(Shell::CmdExe),
+        "cmd /d /c cd /d ^\"/tmp/a b^\"^&^& set OLD=^&^& set MSG=it's^&^& ^^^\"my tool^^^\" \
+         -o ^^^\"out file^^^\" 50^^^% ^< in.txt ^>^> ^\"log file^\" 2^>^&1"
+    );
+}
+
+// rendering for POSIX sh, fish, PowerShell and cmd.exe
+fn render_all(cmds: &crate::CmdList) -> [String; 4] {
+    use crate::Shell;
+
+    [Shell::Posix, Shell::Fish, Shell::PowerShell, Shell::CmdExe].map(|shell| cmds.render(shell))
+}
+
+// `true` with environment variable followed by `echo`
+fn env_list(separator: crate::Separator<'static>) -> crate::CmdList<'static> {
+    use crate::{Cmd, CmdList};
+
+    CmdList::new()
+        .cmd(Cmd::with_name("true").env("A", "1").to_owned())
+        .cmd(Cmd::with_name("echo").param("ok").to_owned())
+        .separator(separator)
+        .to_owned()
+}
+
+#[test]
+fn render_cmd_list_semicolon() {
+    use crate::Separator;
+
+    assert_eq!(
+        render_all(&env_list(Separator::Semicolon)),
+        [
+            "A=1 true ; echo ok",
+            "env A=1 true ; echo ok",
+            "& { $saved = ${env:A}; try { ${env:A} = '1'; true } finally { ${env:A} = $saved } } ; echo ok",
+            "cmd /d /c set A=1^&^& true & echo ok",
+        ]
+    );
+}
+
+#[test]
+fn render_cmd_list_escaped_semicolon() {
+    use crate::Separator;
+
+    assert_eq!(
+        render_all(&env_list(Separator::EscapedSemicolon)),
+        [
+            "A=1 true \\; echo ok",
+            "env A=1 true \\; echo ok",
+            "& { $saved = ${env:A}; try { ${env:A} = '1'; true } finally { ${env:A} = $saved } } `; echo ok",
+            "cmd /d /c set A=1^&^& true ^; echo ok",
+        ]
+    );
+}
+
+#[test]
+fn render_cmd_list_and() {
+    use crate::Separator;
+
+    assert_eq!(
+        render_all(&env_list(Separator::And)),
+        [
+            "A=1 true && echo ok",
+            "env A=1 true; and echo ok",
+            "& { $saved = ${env:A}; try { ${env:A} = '1'; true } finally { ${env:A} = $saved } } && echo ok",
+            "cmd /d /c set A=1^&^& true && echo ok",
+        ]
+    );
+}
+
+#[test]
+fn render_cmd_list_or() {
+    use crate::Separator;
+
+    assert_eq!(
+        render_all(&env_list(Separator::Or)),
+        [
+            "A=1 true || echo ok",
+            "env A=1 true; or echo ok",
+            "& { $saved = ${env:A}; try { ${env:A} = '1'; true } finally { ${env:A} = $saved } } || echo ok",
+            "cmd /d /c set A=1^&^& true || echo ok",
+        ]
+    );
+}
+
+#[test]
+fn render_cmd_list_newline() {
+    use crate::Separator;
+
+    assert_eq!(
+        render_all(&env_list(Separator::Newline)),
+        [
+            "A=1 true\necho ok",
+            "env A=1 true\necho ok",
+            "& { $saved = ${env:A}; try { ${env:A} = '1'; true } finally { ${env:A} = $saved } }\necho ok",
+            "cmd /d /c set A=1^&^& true\necho ok",
+        ]
+    );
+}
+
+#[test]
+fn render_cmd_list_posix() {
+    use crate::{Separator, Shell};
+
+    let cmds = env_list(Separator::And);
+    assert_eq!(cmds.render(Shell::Posix), cmds.to_shell_string());
+}
+
+// `make` followed by `make all` with its own working directory and environment
+fn scoped_list(separator: crate::Separator<'static>) -> crate::CmdList<'static> {
+    use crate::{Cmd, CmdList};
+
+    CmdList::new()
+        .cmd(Cmd::with_name("make").to_owned())
+        .cmd(
+            Cmd::with_name("make")
+                .current_dir("build")
+                .env("CC", "cc")
+                .param("all")
+                .to_owned(),
+        )
+        .separator(separator)
+        .to_owned()
+}
+
+#[test]
+fn render_cmd_list_scoped() {
+    use crate::Separator;
+
+    // working directory and environment of a non-first command apply to that command only
+    assert_eq!(
+        render_all(&scoped_list(Separator::Or)),
+        [
+            "make || (cd build && CC=cc make all)",
+            "make; or env -C build CC=cc make all",
+            "make || & { $saved = ${env:CC}; Push-Location -LiteralPath build; try { \
+             ${env:CC} = 'cc'; make all } finally { Pop-Location; ${env:CC} = $saved } }",
+            "make || cmd /d /c cd /d build^&^& set CC=cc^&^& make all",
+        ]
+    );
+}
+
+#[test]
+fn render_cmd_list_scoped_pipe() {
+    use crate::Separator;
+
+    assert_eq!(
+        render_all(&scoped_list(Separator::Pipe)),
+        [
+            "make | (cd build && CC=cc make all)",
+            "make | env -C build CC=cc make all",
+            "make | & { $saved = ${env:CC}; Push-Location -LiteralPath build; try { \
+             ${env:CC} = 'cc'; $input | make all } finally { Pop-Location; \
+             ${env:CC} = $saved } }",
+            "make | cmd /d /c cd /d build^&^& set CC=cc^&^& make all",
+        ]
+    );
+}
+
+#[test]
+fn render_powershell_env_names() {
+    use crate::{Cmd, Shell};
+
+    // environment variable names are escaped
+    let mut cmd = Cmd::with_name("app");
+    cmd.env_remove("my key").env("A}B", "1");
+    assert_eq!(
+        cmd.render(Shell::PowerShell),
+        "& { $saved = ${env:my key}, ${env:A`}B}; try { \
+         Remove-Item -LiteralPath 'Env:my key' -ErrorAction Ignore; ${env:A`}B} = '1'; app } \
+         finally { ${env:my key}, ${env:A`}B} = $saved } }"
+    );
+}
+
+#[test]
+fn render_cmd_exe_expansion() {
+    use crate::{Cmd, Redirect, Shell};
+
+    // quotes do not prevent variable expansion in cmd.exe
+    let mut cmd = Cmd::with_name("app");
+    cmd.stdout(Redirect::file("50% done!.log"));
+    assert_eq!(
+        cmd.render(Shell::CmdExe),
+        "app > \"50\"^%\" done\"^!\".log\""
+    );
+}
+
+#[test]
+fn render_fish_assignment_program() {
+    use crate::{Cmd, Shell};
+
+    // program name read as an assignment by fish
+    let cmd = Cmd::with_name("A=b");
+    assert_eq!(cmd.render(Shell::Fish), "'A=b'");
+}
+
+#[test]
+fn render_env_program_with_equals() {
+    use crate::{Cmd, Shell};
+
+    // `env` reads any word containing `=` as an assignment, program is started by `sh`
+    let mut cmd = Cmd::with_name("A=b");
+    cmd.env_remove("X").param("c");
+    assert_eq!(
+        cmd.render(Shell::Posix),
+        r#"env -u X sh -c 'exec "$0" "$@"' A=b c"#
+    );
+    assert_eq!(
+        cmd.render(Shell::Fish),
+        r#"env -u X sh -c 'exec "$0" "$@"' A=b c"#
+    );
+
+    // without `env` the quoting is enough
+    let cmd = Cmd::with_name("A=b");
+    assert_eq!(cmd.render(Shell::Posix), "'A=b'");
+}
+
+#[cfg(unix)]
+#[test]
+fn render_env_program_with_equals_run() {
+    use crate::Cmd;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = std::env::temp_dir().join(format!("cmd_builder_shell_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let program = dir.join("A=b");
+    fs::write(&program, "#!/bin/sh\necho \"$@\"\n").unwrap();
+    fs::set_permissions(&program, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut cmd = Cmd::with_name(program.to_string_lossy().into_owned());
+    cmd.env_remove("X").param("c");
+    let output = Cmd::with_name("sh")
+        .opt("-c", cmd.to_shell_string())
+        .output_string()
+        .unwrap();
+    assert_eq!(output, "c\n");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn render_powershell_hyphen_dot() {
+    use crate::{Cmd, Shell};
+
+    // PowerShell before 7.3 splits `-foo.bar` at the dot
+    let cmd = Cmd::with_name("java")
+        .push_flag("-Dfile.encoding=UTF-8")
+        .param("-x")
+        .param("app.jar")
+        .to_owned();
+    assert_eq!(
+        cmd.render(Shell::PowerShell),
+        "java '-Dfile.encoding=UTF-8' -x app.jar"
+    );
+}
@@ -0,0 +1,46 @@
+# bash completion for tmux, generated by cmd_builder
+_tmux() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local prev="${COMP_WORDS[COMP_CWORD-1]}"
+    local path=tmux i
+    COMPREPLY=()
+
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${path} ${COMP_WORDS[i]}" in
+            'tmux new-session'|'tmux new') path='tmux new-session' ;;
+            'tmux set-option'|'tmux set') path='tmux set-option' ;;
+            'tmux source-file') path='tmux source-file' ;;
+            'tmux source-file now') path='tmux source-file now' ;;
+        esac
+    done
+
+    case "${path}" in
+        tmux)
+            case "${prev}" in
+                -f) COMPREPLY=($(compgen -f -- "${cur}")); return ;;
+                -L) return ;;
+            esac
+            COMPREPLY=($(compgen -W '-u -f -L new-session new set-option set source-file' -- "${cur}"))
+            ;;
+        'tmux new-session')
+            case "${prev}" in
+                -s) return ;;
+            esac
+            COMPREPLY=($(compgen -W '-d -s' -- "${cur}"))
+            ;;
+        'tmux set-option')
+            case "${prev}" in
+                --mode) COMPREPLY=($(compgen -W 'vi emacs' -- "${cur}")); return ;;
+            esac
+            COMPREPLY=($(compgen -W '-g --mode' -- "${cur}"))
+            COMPREPLY+=($(compgen -f -- "${cur}"))
+            ;;
+        'tmux source-file')
+            COMPREPLY=($(compgen -W now -- "${cur}"))
+            ;;
+        'tmux source-file now')
+            COMPREPLY=($(compgen -W '-q --quiet' -- "${cur}"))
+            ;;
+    esac
+}
+complete -F _tmux tmux
@@ -0,0 +1,17 @@
+# fish completion for tmux, generated by cmd_builder
+complete -c tmux -f
+complete -c tmux -n __fish_use_subcommand -s u -d 'force UTF-8'
+complete -c tmux -n __fish_use_subcommand -s f -r -F -d 'configuration file'
+complete -c tmux -n __fish_use_subcommand -s L -x -d 'socket name'
+complete -c tmux -n __fish_use_subcommand -a new-session -d 'create a new session'
+complete -c tmux -n __fish_use_subcommand -a new -d 'create a new session'
+complete -c tmux -n __fish_use_subcommand -a set-option -d 'set an option'
+complete -c tmux -n __fish_use_subcommand -a set -d 'set an option'
+complete -c tmux -n __fish_use_subcommand -a source-file -d 'execute commands from file, it\'s like `.`'
+complete -c tmux -n '__fish_seen_subcommand_from new-session new' -s d -d detached
+complete -c tmux -n '__fish_seen_subcommand_from new-session new' -s s -x -d 'session name'
+complete -c tmux -n '__fish_seen_subcommand_from set-option set' -s g -d 'global [server]'
+complete -c tmux -n '__fish_seen_subcommand_from set-option set' -l mode -x -a 'vi emacs'
+complete -c tmux -n '__fish_seen_subcommand_from set-option set' -F
+complete -c tmux -n '__fish_seen_subcommand_from source-file; and not __fish_seen_subcommand_from now' -a now
+complete -c tmux -n '__fish_seen_subcommand_from source-file; and __fish_seen_subcommand_from now' -s q -l quiet
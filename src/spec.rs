@@ -0,0 +1,782 @@
+//! Command specification: allowed flags, options, parameters and subcommands of a program,
+//! used for [`Cmd::validate()`]
+use super::arg::{Arg, FLAGS_ARGS_SEPARATOR};
+use super::Cmd;
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+
+/// Type of option value or parameter
+///
+/// ```text
+/// String  - any string
+/// Integer - signed integer (`-5`, `42`)
+/// Float   - floating point number (`0.5`)
+/// Bool    - `true` or `false`
+/// Path    - non-empty string
+/// Choice  - one of the given strings
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ValueType<'a> {
+    #[default]
+    String,
+    Integer,
+    Float,
+    Bool,
+    Path,
+    Choice(Vec<Cow<'a, str>>),
+}
+
+impl<'a> ValueType<'a> {
+    /// Returns `true` if `value` is valid for the type
+    pub fn is_valid(&self, value: &str) -> bool {
+        match self {
+            ValueType::String => true,
+            ValueType::Integer => value.parse::<i64>().is_ok(),
+            ValueType::Float => value.parse::<f64>().is_ok(),
+            ValueType::Bool => value == "true" || value == "false",
+            ValueType::Path => !value.is_empty(),
+            ValueType::Choice(choices) => choices.iter().any(|choice| choice == value),
+        }
+    }
+}
+
+impl<'a> fmt::Display for ValueType<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValueType::String => write!(f, "string"),
+            ValueType::Integer => write!(f, "integer"),
+            ValueType::Float => write!(f, "float"),
+            ValueType::Bool => write!(f, "bool"),
+            ValueType::Path => write!(f, "path"),
+            ValueType::Choice(choices) => write!(f, "one of {}", choices.join(", ")),
+        }
+    }
+}
+
+/// Flag or option specification, short (`-a`) and/or long (`--all`) name
+///
+/// # Examples
+/// ```
+/// use cmd_builder::{FlagSpec, ValueType};
+///
+/// let flag = FlagSpec::long("jobs").with_short('j').value(ValueType::Integer);
+/// assert_eq!(flag.id(), "--jobs");
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct FlagSpec<'a> {
+    /// short name (`a` = `-a`)
+    pub short: Option<char>,
+
+    /// long name (`all` = `--all`)
+    pub long: Option<Cow<'a, str>>,
+
+    /// value type, `None` = flag without value
+    pub value: Option<ValueType<'a>>,
+
+    /// flag must be present
+    pub required: bool,
+
+    /// maximum number of occurrences, `None` = unlimited
+    pub max_count: Option<usize>,
+
+    /// description, used in completions
+    pub help: Option<Cow<'a, str>>,
+}
+
+impl<'a> FlagSpec<'a> {
+    /// Create `FlagSpec` with short name
+    pub fn short(short: char) -> Self {
+        FlagSpec {
+            short: Some(short),
+            ..Default::default()
+        }
+    }
+
+    /// Create `FlagSpec` with long name
+    pub fn long<S: Into<Cow<'a, str>>>(long: S) -> Self {
+        FlagSpec {
+            long: Some(long.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Set `FlagSpec.short` field
+    pub fn with_short(mut self, short: char) -> Self {
+        self.short = Some(short);
+        self
+    }
+
+    /// Set `FlagSpec.long` field
+    pub fn with_long<S: Into<Cow<'a, str>>>(mut self, long: S) -> Self {
+        self.long = Some(long.into());
+        self
+    }
+
+    /// Set `FlagSpec.value` field, flag becomes an option
+    pub fn value(mut self, value: ValueType<'a>) -> Self {
+        self.value = Some(value);
+        self
+    }
+
+    /// Set `FlagSpec.required` to `true`
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Set `FlagSpec.max_count` field
+    pub fn max_count(mut self, max_count: usize) -> Self {
+        self.max_count = Some(max_count);
+        self
+    }
+
+    /// Set `FlagSpec.help` field
+    pub fn help<S: Into<Cow<'a, str>>>(mut self, help: S) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Flag name used in errors and groups, long if present (`--all`), short otherwise (`-a`)
+    pub fn id(&self) -> String {
+        match (&self.long, self.short) {
+            (Some(long), _) => format!("--{}", long),
+            (None, Some(short)) => format!("-{}", short),
+            (None, None) => String::new(),
+        }
+    }
+
+    // `-a` or `--all`
+    fn matches(&self, name: &str) -> bool {
+        match name.strip_prefix("--") {
+            Some(long) => self.long.as_deref() == Some(long),
+            None => {
+                let mut chars = name.strip_prefix('-').unwrap_or(name).chars();
+                self.short.is_some() && chars.next() == self.short && chars.next().is_none()
+            }
+        }
+    }
+}
+
+/// Positional parameter specification, `min`, `max` number of values (arity)
+#[derive(Debug, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ParamSpec<'a> {
+    /// parameter name, used in errors
+    pub name: Cow<'a, str>,
+
+    /// values type
+    pub value: ValueType<'a>,
+
+    /// minimum number of values
+    pub min: usize,
+
+    /// maximum number of values, `None` = unlimited
+    pub max: Option<usize>,
+}
+
+impl<'a> ParamSpec<'a> {
+    /// Create `ParamSpec` accepting exactly one value
+    pub fn new<S: Into<Cow<'a, str>>>(name: S) -> Self {
+        ParamSpec {
+            name: name.into(),
+            value: ValueType::String,
+            min: 1,
+            max: Some(1),
+        }
+    }
+
+    /// Set `ParamSpec.value` field
+    pub fn value(mut self, value: ValueType<'a>) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Set arity, minimum and maximum number of values (`None` = unlimited)
+    pub fn arity(mut self, min: usize, max: Option<usize>) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    /// Set arity to zero or one value
+    pub fn optional(self) -> Self {
+        self.arity(0, Some(1))
+    }
+}
+
+/// Program specification: flags, options, positional parameters and subcommands
+///
+/// # Examples
+/// ```
+/// use cmd_builder::{Cmd, CmdSpec, FlagSpec, ParamSpec, ValueType};
+///
+/// let spec = CmdSpec::new("git")
+///     .flag(FlagSpec::short('v'))
+///     .subcommand(
+///         CmdSpec::new("log")
+///             .flag(FlagSpec::short('n').value(ValueType::Integer))
+///             .flag(FlagSpec::long("oneline").max_count(1))
+///             .param(ParamSpec::new("path").arity(0, None)),
+///     );
+///
+/// let cmd = Cmd::parse_shell("git -v log -n 5 --oneline src").unwrap();
+/// assert!(cmd.validate(&spec).is_ok());
+///
+/// let cmd = Cmd::parse_shell("git log -n five --online").unwrap();
+/// let errors = cmd.validate(&spec).unwrap_err();
+/// assert_eq!(errors[0].to_string(), "git log: invalid value `five` for -n, expected integer");
+/// assert_eq!(errors[1].to_string(), "git log: unknown flag `--online`");
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct CmdSpec<'a> {
+    /// program or subcommand name
+    pub name: Cow<'a, str>,
+
+    /// alias (`new` for `new-session`)
+    pub alias: Option<Cow<'a, str>>,
+
+    /// allowed flags and options
+    pub flags: Vec<FlagSpec<'a>>,
+
+    /// positional parameters, in order
+    pub params: Vec<ParamSpec<'a>>,
+
+    /// allowed subcommands
+    pub subcommands: Vec<CmdSpec<'a>>,
+
+    /// mutually exclusive flags groups (flag ids, see [`FlagSpec::id()`])
+    pub exclusive: Vec<Vec<Cow<'a, str>>>,
+
+    /// subcommand must be present
+    pub subcommand_required: bool,
+
+    /// description, used in completions
+    pub help: Option<Cow<'a, str>>,
+}
+
+impl<'a> CmdSpec<'a> {
+    pub fn new<S: Into<Cow<'a, str>>>(name: S) -> Self {
+        CmdSpec {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set `CmdSpec.alias` field
+    pub fn alias<S: Into<Cow<'a, str>>>(mut self, alias: S) -> Self {
+        self.alias = Some(alias.into());
+        self
+    }
+
+    /// Add flag or option
+    pub fn flag(mut self, flag: FlagSpec<'a>) -> Self {
+        self.flags.push(flag);
+        self
+    }
+
+    /// Add positional parameter
+    pub fn param(mut self, param: ParamSpec<'a>) -> Self {
+        self.params.push(param);
+        self
+    }
+
+    /// Add subcommand
+    pub fn subcommand(mut self, subcommand: CmdSpec<'a>) -> Self {
+        self.subcommands.push(subcommand);
+        self
+    }
+
+    /// Add mutually exclusive flags group (`["--json", "--text"]`)
+    pub fn exclusive<I, S>(mut self, flags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<Cow<'a, str>>,
+    {
+        self.exclusive
+            .push(flags.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set `CmdSpec.subcommand_required` to `true`
+    pub fn subcommand_required(mut self) -> Self {
+        self.subcommand_required = true;
+        self
+    }
+
+    /// Set `CmdSpec.help` field
+    pub fn help<S: Into<Cow<'a, str>>>(mut self, help: S) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// All names (name and alias)
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.name.as_ref()).chain(self.alias.as_deref())
+    }
+
+    // name or alias equals to `name`
+    fn is_named(&self, name: &str) -> bool {
+        self.name == name || self.alias.as_deref() == Some(name)
+    }
+
+    // flag by name (`-a`, `--all`)
+    fn find_flag(&self, name: &str) -> Option<usize> {
+        self.flags.iter().position(|flag| flag.matches(name))
+    }
+}
+
+/// Validation error, each variant contains the command path (`git log`)
+///
+/// ```text
+/// NameMismatch       - command name differs from specification name
+/// UnknownFlag        - flag not present in specification
+/// UnexpectedValue    - value given for a flag without value
+/// MissingValue       - no value given for an option
+/// InvalidValue       - option or parameter value does not match type
+/// MissingRequired    - required flag is not present
+/// TooManyOccurrences - flag used more times than allowed
+/// Conflict           - more than one flag of mutually exclusive group is present
+/// MissingParam       - less values than parameter minimum
+/// UnexpectedParam    - more parameters than specification allows
+/// UnknownSubcommand  - subcommand not present in specification
+/// MissingSubcommand  - required subcommand is not present
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ValidationError {
+    NameMismatch {
+        command: String,
+        found: String,
+    },
+    UnknownFlag {
+        command: String,
+        flag: String,
+    },
+    UnexpectedValue {
+        command: String,
+        flag: String,
+    },
+    MissingValue {
+        command: String,
+        flag: String,
+    },
+    InvalidValue {
+        command: String,
+        name: String,
+        value: String,
+        expected: String,
+    },
+    MissingRequired {
+        command: String,
+        flag: String,
+    },
+    TooManyOccurrences {
+        command: String,
+        flag: String,
+        max: usize,
+    },
+    Conflict {
+        command: String,
+        flags: Vec<String>,
+    },
+    MissingParam {
+        command: String,
+        name: String,
+    },
+    UnexpectedParam {
+        command: String,
+        value: String,
+    },
+    UnknownSubcommand {
+        command: String,
+        name: String,
+    },
+    MissingSubcommand {
+        command: String,
+    },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValidationError::NameMismatch { command, found } => {
+                write!(f, "{}: unexpected command name `{}`", command, found)
+            }
+            ValidationError::UnknownFlag { command, flag } => {
+                write!(f, "{}: unknown flag `{}`", command, flag)
+            }
+            ValidationError::UnexpectedValue { command, flag } => {
+                write!(f, "{}: {} does not take a value", command, flag)
+            }
+            ValidationError::MissingValue { command, flag } => {
+                write!(f, "{}: {} requires a value", command, flag)
+            }
+            ValidationError::InvalidValue {
+                command,
+                name,
+                value,
+                expected,
+            } => write!(
+                f,
+                "{}: invalid value `{}` for {}, expected {}",
+                command, value, name, expected
+            ),
+            ValidationError::MissingRequired { command, flag } => {
+                write!(f, "{}: required {} is missing", command, flag)
+            }
+            ValidationError::TooManyOccurrences { command, flag, max } => {
+                write!(f, "{}: {} used more than {} time(s)", command, flag, max)
+            }
+            ValidationError::Conflict { command, flags } => {
+                write!(
+                    f,
+                    "{}: {} can not be used together",
+                    command,
+                    flags.join(", ")
+                )
+            }
+            ValidationError::MissingParam { command, name } => {
+                write!(f, "{}: missing parameter <{}>", command, name)
+            }
+            ValidationError::UnexpectedParam { command, value } => {
+                write!(f, "{}: unexpected parameter `{}`", command, value)
+            }
+            ValidationError::UnknownSubcommand { command, name } => {
+                write!(f, "{}: unknown subcommand `{}`", command, name)
+            }
+            ValidationError::MissingSubcommand { command } => {
+                write!(f, "{}: subcommand is required", command)
+            }
+        }
+    }
+}
+
+impl Error for ValidationError {}
+
+// validation state of a single command
+struct Validator<'s, 'a> {
+    spec: &'s CmdSpec<'a>,
+    command: String,
+    counts: Vec<usize>,
+    params: Vec<String>,
+    errors: Vec<ValidationError>,
+}
+
+impl<'s, 'a> Validator<'s, 'a> {
+    fn new(spec: &'s CmdSpec<'a>, command: String) -> Self {
+        Validator {
+            spec,
+            counts: vec![0; spec.flags.len()],
+            command,
+            params: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    // flag occurrence, `value` = value given together with the flag; returns `true` if the
+    // flag expects a value, but none was given
+    fn flag(&mut self, name: &str, value: Option<&str>) -> bool {
+        let Some(i) = self.spec.find_flag(name) else {
+            self.errors.push(ValidationError::UnknownFlag {
+                command: self.command.clone(),
+                flag: name.to_string(),
+            });
+            return false;
+        };
+        self.counts[i] += 1;
+
+        match (&self.spec.flags[i].value, value) {
+            (Some(value_type), Some(value)) => {
+                self.value(name, value_type, value);
+                false
+            }
+            (Some(_), None) => true,
+            (None, Some(_)) => {
+                self.errors.push(ValidationError::UnexpectedValue {
+                    command: self.command.clone(),
+                    flag: name.to_string(),
+                });
+                false
+            }
+            (None, None) => false,
+        }
+    }
+
+    fn value(&mut self, name: &str, value_type: &ValueType, value: &str) {
+        if !value_type.is_valid(value) {
+            self.errors.push(ValidationError::InvalidValue {
+                command: self.command.clone(),
+                name: name.to_string(),
+                value: value.to_string(),
+                expected: value_type.to_string(),
+            });
+        }
+    }
+
+    // flag argument as written (`-a`, `-abc`, `-Ivalue`, `--all`, `--target=value`), returns
+    // the flag waiting for a value (next parameter)
+    fn flag_arg(&mut self, arg: &str) -> Option<String> {
+        if let Some(long) = arg.strip_prefix("--") {
+            let (name, value) = match long.split_once('=') {
+                Some((name, value)) => (format!("--{}", name), Some(value)),
+                None => (arg.to_string(), None),
+            };
+            return self.flag(&name, value).then_some(name);
+        }
+
+        // short flags cluster, the rest after a flag with value is its value
+        let shorts = arg.strip_prefix('-').unwrap_or(arg);
+        for (i, c) in shorts.char_indices() {
+            let name = format!("-{}", c);
+            let rest = &shorts[i + c.len_utf8()..];
+            let takes_value = self
+                .spec
+                .find_flag(&name)
+                .is_some_and(|flag| self.spec.flags[flag].value.is_some());
+            if takes_value && !rest.is_empty() {
+                self.flag(&name, Some(rest));
+                return None;
+            }
+            if self.flag(&name, None) {
+                return rest.is_empty().then_some(name);
+            }
+        }
+        None
+    }
+
+    // checks after all arguments are processed
+    fn finish(mut self) -> Vec<ValidationError> {
+        for (flag, &count) in self.spec.flags.iter().zip(&self.counts) {
+            if flag.required && count == 0 {
+                self.errors.push(ValidationError::MissingRequired {
+                    command: self.command.clone(),
+                    flag: flag.id(),
+                });
+            }
+            if let Some(max) = flag.max_count.filter(|&max| count > max) {
+                self.errors.push(ValidationError::TooManyOccurrences {
+                    command: self.command.clone(),
+                    flag: flag.id(),
+                    max,
+                });
+            }
+        }
+
+        for group in &self.spec.exclusive {
+            let present: Vec<String> = self
+                .spec
+                .flags
+                .iter()
+                .zip(&self.counts)
+                .filter(|(flag, &count)| count > 0 && group.iter().any(|id| flag.id() == *id))
+                .map(|(flag, _)| flag.id())
+                .collect();
+            if present.len() > 1 {
+                self.errors.push(ValidationError::Conflict {
+                    command: self.command.clone(),
+                    flags: present,
+                });
+            }
+        }
+
+        self.check_params();
+        self.errors
+    }
+
+    // assign parameters to specifications in order, each takes as many values as possible,
+    // leaving the minimum for the following ones
+    fn check_params(&mut self) {
+        let params = std::mem::take(&mut self.params);
+        let mut rest = params.as_slice();
+
+        for (i, spec) in self.spec.params.iter().enumerate() {
+            let reserved: usize = self.spec.params[i + 1..].iter().map(|p| p.min).sum();
+            let available = rest.len().saturating_sub(reserved);
+            let take = spec.max.map_or(available, |max| available.min(max));
+
+            if take < spec.min {
+                self.errors.push(ValidationError::MissingParam {
+                    command: self.command.clone(),
+                    name: spec.name.to_string(),
+                });
+            }
+            let (values, tail) = rest.split_at(take.min(rest.len()));
+            for value in values {
+                let name = format!("<{}>", spec.name);
+                self.value(&name, &spec.value, value);
+            }
+            rest = tail;
+        }
+
+        for value in rest {
+            self.errors.push(ValidationError::UnexpectedParam {
+                command: self.command.clone(),
+                value: value.clone(),
+            });
+        }
+    }
+}
+
+impl<'a> Cmd<'a> {
+    /// Check command against the specification: name, flags, option values, parameters
+    /// arity, subcommands (recursively), all errors are collected
+    ///
+    /// Options and parameters can not be distinguished in parsed commands (see
+    /// [`Cmd::parse_shell()`]), so parameter following an option without value is used as its
+    /// value, [`Arg::Raw`] arguments are not checked. `-` and negative numbers (`-1`) are
+    /// parameters, only the first parameter can be a subcommand (`git add log`)
+    pub fn validate(&self, spec: &CmdSpec) -> Result<(), Vec<ValidationError>> {
+        let errors = self.validate_path(spec, None);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn validate_path(&self, spec: &CmdSpec, parent: Option<&str>) -> Vec<ValidationError> {
+        let command = match parent {
+            Some(parent) => format!("{} {}", parent, spec.name),
+            None => spec.name.to_string(),
+        };
+        let mut errors = Vec::new();
+
+        let found = self.name.as_deref().or(self.alias.as_deref());
+        let matches = [self.name.as_deref(), self.alias.as_deref()]
+            .into_iter()
+            .flatten()
+            .any(|name| spec.is_named(name));
+        if let (Some(found), false) = (found, matches) {
+            errors.push(ValidationError::NameMismatch {
+                command: command.clone(),
+                found: found.to_string(),
+            });
+        }
+
+        // short flags (`-abc`), the last one may take the following parameter as value
+        let mut validator = Validator::new(spec, command.clone());
+        let mut pending: Option<String> = None;
+        let flags_short = self.flags_short.as_deref().unwrap_or_default();
+        // parsed negative number (`seq -5 5`)
+        let (negative, flags_short) = match flags_short {
+            "" => (None, ""),
+            flags if is_param_like(spec, &format!("-{}", flags)) => (Some(flags), ""),
+            flags => (None, flags),
+        };
+        if let Some(number) = negative {
+            validator.params.push(format!("-{}", number));
+        }
+        for c in flags_short.chars() {
+            if let Some(flag) = pending.take() {
+                validator.errors.push(ValidationError::MissingValue {
+                    command: command.clone(),
+                    flag,
+                });
+            }
+            let name = format!("-{}", c);
+            if validator.flag(&name, None) {
+                pending = Some(name);
+            }
+        }
+
+        let args = self.args.as_deref().unwrap_or_default();
+        let mut separated = false;
+        let mut implicit_subcommand = None;
+        for (i, arg) in args.iter().enumerate() {
+            let param = match arg {
+                Arg::Param(param) => Some(param.as_ref()),
+                Arg::Flag(flag) if separated || is_param_like(spec, flag) => Some(flag.as_ref()),
+                _ => None,
+            };
+            if let Some(flag) = pending.take() {
+                match param {
+                    Some(value) if !separated => {
+                        let value_type = spec
+                            .find_flag(&flag)
+                            .and_then(|i| spec.flags[i].value.as_ref());
+                        if let Some(value_type) = value_type {
+                            validator.value(&flag, value_type, value);
+                        }
+                        continue;
+                    }
+                    _ => validator.errors.push(ValidationError::MissingValue {
+                        command: command.clone(),
+                        flag,
+                    }),
+                }
+            }
+
+            // parsed command line (`git log -n 5`), subcommand is the first parameter
+            if let Some(name) = param.filter(|_| !separated && validator.params.is_empty()) {
+                if spec.subcommands.iter().any(|sub| sub.is_named(name)) {
+                    implicit_subcommand = Some(Cmd {
+                        name: Some(Cow::Borrowed(name)),
+                        args: Some(args[i + 1..].to_vec()),
+                        ..Default::default()
+                    });
+                    break;
+                }
+            }
+
+            match arg {
+                _ if param.is_some() => validator.params.extend(param.map(str::to_string)),
+                Arg::Flag(flag) if flag == FLAGS_ARGS_SEPARATOR => separated = true,
+                Arg::Flag(flag) => pending = validator.flag_arg(flag),
+                // value is present, the flag never waits for the next argument
+                Arg::Option { key, value, .. } => {
+                    validator.flag(key, Some(value));
+                }
+                Arg::Separator => separated = true,
+                Arg::Param(_) | Arg::Raw(_) => {}
+            }
+        }
+        if let Some(flag) = pending {
+            validator.errors.push(ValidationError::MissingValue {
+                command: command.clone(),
+                flag,
+            });
+        }
+        errors.extend(validator.finish());
+
+        let subcommands = self.subcommands.iter().flat_map(|cmds| &cmds.commands);
+        let mut has_subcommand = false;
+        for cmd in implicit_subcommand.iter().chain(subcommands) {
+            has_subcommand = true;
+            let name = cmd
+                .name
+                .as_deref()
+                .or(cmd.alias.as_deref())
+                .unwrap_or_default();
+            let sub_spec = spec.subcommands.iter().find(|sub| {
+                [cmd.name.as_deref(), cmd.alias.as_deref()]
+                    .into_iter()
+                    .flatten()
+                    .any(|name| sub.is_named(name))
+            });
+            match sub_spec {
+                Some(sub_spec) => errors.extend(cmd.validate_path(sub_spec, Some(&command))),
+                None => errors.push(ValidationError::UnknownSubcommand {
+                    command: command.clone(),
+                    name: name.to_string(),
+                }),
+            }
+        }
+        if spec.subcommand_required && !has_subcommand {
+            errors.push(ValidationError::MissingSubcommand { command });
+        }
+
+        errors
+    }
+}
+
+// `-` (stdin) and negative numbers (`-1`, `-0.5`) are parameters, unless the spec has a flag
+// with the first digit (`ls -1`)
+fn is_param_like(spec: &CmdSpec, arg: &str) -> bool {
+    let number = match arg.strip_prefix('-') {
+        Some("") => return true,
+        Some(number) => number,
+        None => return false,
+    };
+    number.starts_with(|c: char| c.is_ascii_digit())
+        && number.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && spec.find_flag(&arg[..2]).is_none()
+}
@@ -0,0 +1,256 @@
+// tar with exclusive flags, required option with value, choice and parameters
+fn tar_spec() -> crate::CmdSpec<'static> {
+    use crate::{CmdSpec, FlagSpec, ParamSpec, ValueType};
+
+    CmdSpec::new("tar")
+        .flag(FlagSpec::short('c'))
+        .flag(FlagSpec::short('x'))
+        .flag(FlagSpec::short('v').max_count(2))
+        .flag(
+            FlagSpec::short('f')
+                .with_long("file")
+                .value(ValueType::Path)
+                .required(),
+        )
+        .flag(FlagSpec::long("format").value(ValueType::Choice(vec!["gnu".into(), "posix".into()])))
+        .exclusive(["-c", "-x"])
+        .param(ParamSpec::new("dest").optional())
+        .param(ParamSpec::new("files").arity(1, None))
+}
+
+#[test]
+fn spec_validate() {
+    use crate::Cmd;
+
+    let spec = tar_spec();
+    let cmd = Cmd::parse_shell("tar -cvf a.tar --format=gnu -- src -x").unwrap();
+    assert_eq!(cmd.validate(&spec), Ok(()));
+    let cmd = Cmd::parse_shell("tar -cv --file=a.tar src").unwrap();
+    assert_eq!(cmd.validate(&spec), Ok(()));
+}
+
+#[test]
+fn spec_validate_errors() {
+    use crate::{Cmd, ValidationError};
+
+    let cmd = Cmd::with_name("tar")
+        .push_flag_short('c')
+        .push_flag_short('x')
+        .push_flag("-vvv")
+        .push_option("--format", "zip")
+        .push_flag("--file")
+        .to_owned();
+    let command = String::from("tar");
+    assert_eq!(
+        cmd.validate(&tar_spec()),
+        Err(vec![
+            ValidationError::InvalidValue {
+                command: command.clone(),
+                name: "--format".into(),
+                value: "zip".into(),
+                expected: "one of gnu, posix".into(),
+            },
+            ValidationError::MissingValue {
+                command: command.clone(),
+                flag: "--file".into(),
+            },
+            ValidationError::TooManyOccurrences {
+                command: command.clone(),
+                flag: "-v".into(),
+                max: 2,
+            },
+            ValidationError::Conflict {
+                command: command.clone(),
+                flags: vec!["-c".into(), "-x".into()],
+            },
+            ValidationError::MissingParam {
+                command,
+                name: "files".into(),
+            },
+        ])
+    );
+}
+
+#[test]
+fn spec_validate_error_display() {
+    use crate::Cmd;
+
+    let errors = Cmd::parse_shell("tar -z a b")
+        .unwrap()
+        .validate(&tar_spec())
+        .unwrap_err();
+    assert_eq!(
+        errors.iter().map(|e| e.to_string()).collect::<Vec<_>>(),
+        vec!["tar: unknown flag `-z`", "tar: required --file is missing"]
+    );
+}
+
+// tmux with required `new-session` (alias `new`) or `kill-server` subcommand
+fn tmux_spec() -> crate::CmdSpec<'static> {
+    use crate::{CmdSpec, FlagSpec};
+
+    CmdSpec::new("tmux")
+        .subcommand(
+            CmdSpec::new("new-session")
+                .alias("new")
+                .flag(FlagSpec::short('d'))
+                .flag(FlagSpec::short('s').value(Default::default())),
+        )
+        .subcommand(CmdSpec::new("kill-server"))
+        .subcommand_required()
+}
+
+#[test]
+fn spec_validate_subcommands() {
+    use crate::{Cmd, CmdList};
+
+    let mut cmd = Cmd::with_name("tmux");
+    cmd.push_cmds(
+        CmdList::new()
+            .cmd(Cmd::with_alias("new").push_flag_short('d').to_owned())
+            .cmd(Cmd::with_name("kill-server").to_owned()),
+    );
+    assert_eq!(cmd.validate(&tmux_spec()), Ok(()));
+}
+
+#[test]
+fn spec_validate_subcommand_unexpected_param() {
+    use crate::{Cmd, ValidationError};
+
+    let cmd = Cmd::parse_shell("tmux new -d -s x extra").unwrap();
+    assert_eq!(
+        cmd.validate(&tmux_spec()),
+        Err(vec![ValidationError::UnexpectedParam {
+            command: "tmux new-session".into(),
+            value: "extra".into(),
+        }])
+    );
+}
+
+#[test]
+fn spec_validate_subcommand_required() {
+    use crate::Cmd;
+
+    let errors = Cmd::parse_shell("tmux")
+        .unwrap()
+        .validate(&tmux_spec())
+        .unwrap_err();
+    assert_eq!(errors[0].to_string(), "tmux: subcommand is required");
+}
+
+#[test]
+fn spec_validate_unknown_subcommand() {
+    use crate::Cmd;
+
+    let mut cmd = Cmd::with_name("tmux");
+    cmd.push_cmd(Cmd::with_name("attach"));
+    let errors = cmd.validate(&tmux_spec()).unwrap_err();
+    assert_eq!(errors[0].to_string(), "tmux: unknown subcommand `attach`");
+}
+
+#[test]
+fn spec_validate_command_name() {
+    use crate::{Cmd, CmdSpec, ParamSpec};
+
+    let spec = CmdSpec::new("ls").param(ParamSpec::new("dir").optional());
+    let errors = Cmd::with_name("dir").validate(&spec).unwrap_err();
+    assert_eq!(errors[0].to_string(), "ls: unexpected command name `dir`");
+}
+
+#[test]
+fn spec_validate_stdin_param() {
+    use crate::{Cmd, CmdSpec, FlagSpec, ParamSpec};
+
+    let spec = CmdSpec::new("cat")
+        .flag(FlagSpec::short('n'))
+        .param(ParamSpec::new("files").arity(1, None));
+
+    let cmd = Cmd::parse_shell("cat -n - file").unwrap();
+    assert_eq!(cmd.validate(&spec), Ok(()));
+    let cmd = Cmd::with_name("cat").push_flag("-").to_owned();
+    assert_eq!(cmd.validate(&spec), Ok(()));
+}
+
+#[test]
+fn spec_validate_negative_number() {
+    use crate::{Cmd, CmdSpec, FlagSpec, ParamSpec, ValueType};
+
+    let spec = CmdSpec::new("seq")
+        .flag(FlagSpec::short('s').value(ValueType::String))
+        .param(ParamSpec::new("first").optional())
+        .param(ParamSpec::new("last"));
+
+    assert_eq!(
+        Cmd::parse_shell("seq -5 5").unwrap().validate(&spec),
+        Ok(())
+    );
+    assert_eq!(
+        Cmd::parse_shell("seq -s , -1.5 -0.5")
+            .unwrap()
+            .validate(&spec),
+        Ok(())
+    );
+}
+
+#[test]
+fn spec_validate_negative_option_value() {
+    use crate::{Cmd, CmdSpec, FlagSpec, ParamSpec, ValueType};
+
+    let spec = CmdSpec::new("nice")
+        .flag(FlagSpec::short('n').value(ValueType::Integer))
+        .param(ParamSpec::new("command"));
+    let cmd = Cmd::with_name("nice")
+        .push_flag("-n")
+        .push_flag("-5")
+        .param("make")
+        .to_owned();
+    assert_eq!(cmd.validate(&spec), Ok(()));
+}
+
+#[test]
+fn spec_validate_digit_flag() {
+    use crate::{Cmd, CmdSpec, FlagSpec, ValidationError};
+
+    // flag with digit name is still a flag
+    let spec = CmdSpec::new("ls").flag(FlagSpec::short('1'));
+    assert_eq!(Cmd::parse_shell("ls -1").unwrap().validate(&spec), Ok(()));
+
+    // other negative numbers are parameters
+    assert_eq!(
+        Cmd::parse_shell("ls -2").unwrap().validate(&spec),
+        Err(vec![ValidationError::UnexpectedParam {
+            command: "ls".into(),
+            value: "-2".into(),
+        }])
+    );
+}
+
+#[test]
+fn spec_validate_subcommand_position() {
+    use crate::{Cmd, CmdSpec, ParamSpec};
+
+    let spec = CmdSpec::new("git")
+        .subcommand(CmdSpec::new("add").param(ParamSpec::new("files").arity(1, None)))
+        .subcommand(CmdSpec::new("log"));
+
+    // `log` is a file name, not a subcommand of `git add`
+    let cmd = Cmd::parse_shell("git add log").unwrap();
+    assert_eq!(cmd.validate(&spec), Ok(()));
+}
+
+#[test]
+fn spec_validate_param_before_subcommand() {
+    use crate::{Cmd, CmdSpec, ParamSpec, ValidationError};
+
+    let spec = CmdSpec::new("tool")
+        .param(ParamSpec::new("file"))
+        .subcommand(CmdSpec::new("run"));
+    let cmd = Cmd::parse_shell("tool input run").unwrap();
+    assert_eq!(
+        cmd.validate(&spec),
+        Err(vec![ValidationError::UnexpectedParam {
+            command: "tool".into(),
+            value: "run".into(),
+        }])
+    );
+}
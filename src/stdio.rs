@@ -0,0 +1,247 @@
+use super::quote::posix_quote;
+use std::borrow::Cow;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::process::{Command, Stdio};
+
+/// null device, used for rendering [`Redirect::Null`]
+const DEV_NULL: &str = "/dev/null";
+/// command writing data, used for rendering [`Redirect::Bytes`]
+const PRINTF_CMD: &str = "printf";
+
+/// Standard stream (stdin, stdout, stderr) configuration of the command
+///
+/// ```text
+/// Inherit - inherit from the parent process
+/// Null    - null device (`< /dev/null`, `> /dev/null`)
+/// Piped   - pipe to the parent process (captured by `Cmd::output()`)
+/// File    - read from file or write to file, truncating it (`< in.txt`, `> out.log`)
+/// Append  - write to file, appending (`>> out.log`)
+/// Bytes   - in-memory data written to stdin (`printf 'data' | cmd`, stdin only)
+/// Stdout  - same as stdout (`2>&1`, stderr only)
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Redirect<'a> {
+    Inherit,
+    Null,
+    Piped,
+    File(Cow<'a, str>),
+    Append(Cow<'a, str>),
+    Bytes(Cow<'a, [u8]>),
+    Stdout,
+}
+
+impl<'a> Redirect<'a> {
+    /// Create `Redirect::File`
+    pub fn file<S: Into<Cow<'a, str>>>(path: S) -> Self {
+        Redirect::File(path.into())
+    }
+
+    /// Create `Redirect::Append`
+    pub fn append<S: Into<Cow<'a, str>>>(path: S) -> Self {
+        Redirect::Append(path.into())
+    }
+
+    /// Create `Redirect::Bytes`
+    pub fn bytes<B: Into<Cow<'a, [u8]>>>(bytes: B) -> Self {
+        Redirect::Bytes(bytes.into())
+    }
+
+    /// Convert to `Redirect` owning all data
+    pub fn into_owned(self) -> Redirect<'static> {
+        match self {
+            Redirect::Inherit => Redirect::Inherit,
+            Redirect::Null => Redirect::Null,
+            Redirect::Piped => Redirect::Piped,
+            Redirect::File(path) => Redirect::File(Cow::Owned(path.into_owned())),
+            Redirect::Append(path) => Redirect::Append(Cow::Owned(path.into_owned())),
+            Redirect::Bytes(bytes) => Redirect::Bytes(Cow::Owned(bytes.into_owned())),
+            Redirect::Stdout => Redirect::Stdout,
+        }
+    }
+
+    /// POSIX sh redirection for stdin, `None` if not representable (inherit, pipe, bytes)
+    pub fn to_stdin_string(&self) -> Option<String> {
+        self.to_stdin_string_with(posix_quote)
+    }
+
+    /// POSIX sh command writing in-memory stdin, to be piped into the command (`printf 'a\n' |
+    /// cmd`), `None` for other redirections. `%`, `\`, control characters and invalid UTF-8 bytes
+    /// are escaped in the `printf` format
+    ///
+    /// # Examples
+    /// ```
+    /// use cmd_builder::Redirect;
+    ///
+    /// let stdin = Redirect::bytes(&b"100%\n"[..]);
+    /// assert_eq!(stdin.to_printf_string().as_deref(), Some(r"printf '100%%\n'"));
+    /// assert_eq!(Redirect::Null.to_printf_string(), None);
+    /// ```
+    pub fn to_printf_string(&self) -> Option<String> {
+        self.to_printf_string_with(posix_quote)
+    }
+
+    // sh-like `printf` writing in-memory stdin, format quoted using `quote`
+    pub(crate) fn to_printf_string_with(&self, quote: fn(&str) -> Cow<'_, str>) -> Option<String> {
+        match self {
+            Redirect::Bytes(bytes) => {
+                Some(format!("{} {}", PRINTF_CMD, quote(&printf_format(bytes))))
+            }
+            _ => None,
+        }
+    }
+
+    /// POSIX sh redirection for stdout (`fd` = 1) or stderr (`fd` = 2), `None` if not
+    /// representable (inherit, pipe)
+    pub fn to_output_string(&self, fd: u8) -> Option<String> {
+        self.to_output_string_with(fd, posix_quote)
+    }
+
+    // sh-like stdin redirection, path quoted using `quote`
+    pub(crate) fn to_stdin_string_with(&self, quote: fn(&str) -> Cow<'_, str>) -> Option<String> {
+        match self {
+            Redirect::Null => Some(format!("< {}", DEV_NULL)),
+            Redirect::File(path) | Redirect::Append(path) => Some(format!("< {}", quote(path))),
+            _ => None,
+        }
+    }
+
+    // sh-like stdout, stderr redirection, path quoted using `quote`
+    pub(crate) fn to_output_string_with(
+        &self,
+        fd: u8,
+        quote: fn(&str) -> Cow<'_, str>,
+    ) -> Option<String> {
+        let fd = if fd == 1 { "" } else { "2" };
+        match self {
+            Redirect::Null => Some(format!("{}> {}", fd, DEV_NULL)),
+            Redirect::File(path) => Some(format!("{}> {}", fd, quote(path))),
+            Redirect::Append(path) => Some(format!("{}>> {}", fd, quote(path))),
+            Redirect::Stdout if !fd.is_empty() => Some(format!("{}>&1", fd)),
+            _ => None,
+        }
+    }
+
+    // stdio without opening files, `None` for files and `Stdout`
+    pub(crate) fn to_stdio(&self) -> Option<Stdio> {
+        match self {
+            Redirect::Inherit => Some(Stdio::inherit()),
+            Redirect::Null => Some(Stdio::null()),
+            Redirect::Piped | Redirect::Bytes(_) => Some(Stdio::piped()),
+            _ => None,
+        }
+    }
+
+    // stdio for stdin, opening file for reading
+    pub(crate) fn open_stdin(&self) -> io::Result<Stdio> {
+        match self {
+            Redirect::File(path) | Redirect::Append(path) => Ok(File::open(path.as_ref())?.into()),
+            Redirect::Stdout => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "stdin can not be redirected to stdout",
+            )),
+            redirect => Ok(redirect.to_stdio().unwrap_or_else(Stdio::inherit)),
+        }
+    }
+
+    // file for stdout, stderr, opened for writing (`None` if not a file)
+    pub(crate) fn open_output(&self) -> io::Result<Option<File>> {
+        match self {
+            Redirect::File(path) => Ok(Some(File::create(path.as_ref())?)),
+            Redirect::Append(path) => Ok(Some(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path.as_ref())?,
+            )),
+            Redirect::Bytes(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "bytes can be used for stdin only",
+            )),
+            _ => Ok(None),
+        }
+    }
+}
+
+// apply stdio configuration to `command` (files are opened), not configured streams are captured
+// (`capture`, stdin is null) or inherited
+pub(crate) fn apply_stdio(
+    command: &mut Command,
+    stdin: Option<&Redirect>,
+    stdout: Option<&Redirect>,
+    stderr: Option<&Redirect>,
+    capture: bool,
+) -> io::Result<()> {
+    match stdin {
+        Some(stdin) => command.stdin(stdin.open_stdin()?),
+        None if capture => command.stdin(Stdio::null()),
+        None => command.stdin(Stdio::inherit()),
+    };
+
+    let default = if capture {
+        Redirect::Piped
+    } else {
+        Redirect::Inherit
+    };
+    let stdout = stdout.unwrap_or(&default);
+    let stderr = stderr.unwrap_or(&default);
+
+    let stdout_file = stdout.open_output()?;
+    match (stderr, stdout, &stdout_file) {
+        (Redirect::Stdout, _, Some(file)) => command.stderr(file.try_clone()?),
+        (Redirect::Stdout, Redirect::Null, _) => command.stderr(Stdio::null()),
+        (Redirect::Stdout, Redirect::Inherit, _) => command.stderr(io::stdout()),
+        (Redirect::Stdout, _, _) => {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "stderr can be redirected to stdout only if stdout is a file, null or inherited",
+            ))
+        }
+        (stderr, _, _) => match stderr.open_output()? {
+            Some(file) => command.stderr(file),
+            None => command.stderr(stderr.to_stdio().unwrap_or_else(Stdio::inherit)),
+        },
+    };
+
+    match (stdout, stdout_file) {
+        (_, Some(file)) => command.stdout(file),
+        (Redirect::Stdout, _) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "stdout can not be redirected to stdout",
+            ))
+        }
+        (stdout, None) => command.stdout(stdout.to_stdio().unwrap_or_else(Stdio::inherit)),
+    };
+
+    Ok(())
+}
+
+// `printf` format printing `bytes` as is, bytes other than printable characters are written as
+// three digit octal escapes (`\ooo`), so a following digit is not taken as part of the escape
+fn printf_format(bytes: &[u8]) -> String {
+    let mut format = String::new();
+    for chunk in bytes.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            match c {
+                '%' => format.push_str("%%"),
+                '\\' => format.push_str("\\\\"),
+                '\n' => format.push_str("\\n"),
+                '\t' => format.push_str("\\t"),
+                c if c.is_control() => {
+                    let mut buf = [0; 4];
+                    for b in c.encode_utf8(&mut buf).bytes() {
+                        format.push_str(&format!("\\{:03o}", b));
+                    }
+                }
+                c => format.push(c),
+            }
+        }
+        for b in chunk.invalid() {
+            format.push_str(&format!("\\{:03o}", b));
+        }
+    }
+    format
+}
@@ -0,0 +1,133 @@
+// temporary directory with `in.txt` containing `hello`, removed by the caller
+#[cfg(unix)]
+fn stdio_dir(name: &str) -> std::path::PathBuf {
+    use std::fs;
+
+    let dir =
+        std::env::temp_dir().join(format!("cmd_builder_stdio_{}_{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("in.txt"), "hello\n").unwrap();
+    dir
+}
+
+#[cfg(unix)]
+#[test]
+fn stdio_bytes() {
+    use crate::{Cmd, Redirect};
+
+    // in-memory stdin, captured stdout
+    let mut cmd = Cmd::with_name("tr");
+    cmd.param("a-z")
+        .param("A-Z")
+        .stdin(Redirect::bytes(&b"bytes"[..]));
+    assert_eq!(cmd.to_string(), "printf bytes | tr a-z A-Z");
+    assert_eq!(cmd.output_string().unwrap(), "BYTES");
+}
+
+#[cfg(unix)]
+#[test]
+fn stdio_file() {
+    use crate::{Cmd, Redirect};
+    use std::fs;
+
+    let dir = stdio_dir("file");
+    let input = dir.join("in.txt").to_string_lossy().into_owned();
+    let log = dir.join("out.log").to_string_lossy().into_owned();
+
+    // file stdin, file stdout, stderr into stdout
+    let mut cmd = Cmd::with_name("sh");
+    cmd.opt("-c", "cat; echo oops >&2")
+        .stdin(Redirect::file(input.as_str()))
+        .stdout(Redirect::file(log.as_str()))
+        .stderr(Redirect::Stdout);
+    assert_eq!(
+        cmd.to_string(),
+        format!("sh -c 'cat; echo oops >&2' < {} > {} 2>&1", input, log)
+    );
+    let output = cmd.run().unwrap();
+    assert!(output.stdout.is_empty());
+    assert_eq!(fs::read_to_string(&log).unwrap(), "hello\noops\n");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn stdio_append_null() {
+    use crate::{Cmd, Redirect};
+    use std::fs;
+
+    let dir = stdio_dir("append");
+    let input = dir.join("in.txt").to_string_lossy().into_owned();
+    let log = dir.join("out.log").to_string_lossy().into_owned();
+    fs::write(&log, "log\n").unwrap();
+
+    let mut cmd = Cmd::with_name("sh");
+    cmd.opt("-c", "cat; echo oops >&2")
+        .stdin(Redirect::file(input.as_str()))
+        .stdout(Redirect::append(log.as_str()))
+        .stderr(Redirect::Null);
+    assert!(cmd
+        .to_string()
+        .ends_with(&format!(">> {} 2> /dev/null", log)));
+    cmd.status().unwrap();
+    assert_eq!(fs::read_to_string(&log).unwrap(), "log\nhello\n");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn stdio_stderr_into_piped_stdout() {
+    use crate::{Cmd, Redirect};
+
+    // stderr can not be merged into captured stdout
+    let mut cmd = Cmd::with_name("sh");
+    cmd.opt("-c", "echo oops >&2")
+        .stdout(Redirect::Piped)
+        .stderr(Redirect::Stdout);
+    assert!(cmd.output().is_err());
+}
+
+#[test]
+fn stdio_bytes_rendered() {
+    use crate::{Cmd, Redirect};
+
+    let mut cmd = Cmd::with_name("od");
+    cmd.push_flag("-c")
+        .current_dir("/tmp")
+        .stdin(Redirect::bytes(&b"it's 100%\\\n\t\x01\xff7"[..]));
+    assert_eq!(
+        cmd.to_string(),
+        r"(cd /tmp && printf 'it'\''s 100%%\\\n\t\001\3777' | od -c)"
+    );
+}
+
+#[test]
+fn stdio_bytes_in_script() {
+    use crate::{Cmd, CmdList, Redirect, ScriptOptions};
+
+    let mut cmd = Cmd::with_name("od");
+    cmd.push_flag("-c")
+        .stdin(Redirect::bytes(&b"it's 100%"[..]));
+    let script = CmdList::new()
+        .cmd(cmd.to_owned())
+        .to_script(&ScriptOptions::default());
+    assert!(script.ends_with("printf 'it'\\''s 100%%' | od -c\n"));
+}
+
+// rendered `printf` writes the same bytes
+#[cfg(unix)]
+#[test]
+fn stdio_bytes_round_trip() {
+    use crate::{Cmd, Redirect};
+
+    let bytes = &b"it's 100%\\\n\t\x01\xff7 \xc3\xa9"[..];
+    let mut cmd = Cmd::with_name("cat");
+    cmd.stdin(Redirect::bytes(bytes));
+    let output = Cmd::with_name("sh")
+        .opt("-c", cmd.to_string())
+        .output()
+        .unwrap();
+    assert_eq!(output.stdout, bytes);
+}
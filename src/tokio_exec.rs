@@ -0,0 +1,108 @@
+//! Asynchronous execution using [`tokio::process::Command`] (`tokio` feature)
+//!
+//! [`tokio::process::Command`] is created from [`std::process::Command`], so arguments are
+//! exactly the same as for synchronous execution
+use super::exec::{CmdError, CmdOutput};
+use super::stdio::Redirect;
+use super::{Cmd, CmdList};
+use std::io;
+use std::process::ExitStatus;
+use std::time::Instant;
+use tokio::io::AsyncWriteExt;
+
+impl<'a> Cmd<'a> {
+    /// Transform `Cmd` into [`tokio::process::Command`] (see [`Cmd::to_command()`])
+    pub fn to_tokio_command(&self) -> tokio::process::Command {
+        tokio::process::Command::from(self)
+    }
+
+    /// Execute command asynchronously (see [`Cmd::output()`])
+    pub async fn output_async(&self) -> Result<CmdOutput, CmdError> {
+        self.execute_async(true).await
+    }
+
+    // spawn, write in-memory stdin, wait for exit
+    async fn execute_async(&self, capture: bool) -> Result<CmdOutput, CmdError> {
+        let io_error = |error| CmdError::Io {
+            command: self.to_string(),
+            error,
+        };
+
+        let command = self.to_command_stdio(capture).map_err(io_error)?;
+        let start = Instant::now();
+        let mut child = tokio::process::Command::from(command)
+            .spawn()
+            .map_err(io_error)?;
+
+        let stdin = child.stdin.take();
+        let write = async {
+            if let (Some(Redirect::Bytes(bytes)), Some(mut stdin)) = (&self.stdin, stdin) {
+                match stdin.write_all(bytes).await {
+                    // process exited without reading all input
+                    Err(error) if error.kind() != io::ErrorKind::BrokenPipe => return Err(error),
+                    _ => {}
+                }
+            }
+            Ok(())
+        };
+
+        let (write, output) = tokio::join!(write, child.wait_with_output());
+        let output = output.map_err(io_error)?;
+        write.map_err(io_error)?;
+
+        Ok(CmdOutput::new(output, start.elapsed()))
+    }
+
+    /// Execute command asynchronously, non-zero exit code is an error (see [`Cmd::run()`])
+    pub async fn run_async(&self) -> Result<CmdOutput, CmdError> {
+        let output = self.output_async().await?;
+        if output.success() {
+            Ok(output)
+        } else {
+            Err(CmdError::Failed {
+                command: self.to_string(),
+                output,
+            })
+        }
+    }
+
+    /// Execute command asynchronously with inherited stdio (see [`Cmd::status()`])
+    pub async fn status_async(&self) -> Result<ExitStatus, CmdError> {
+        self.execute_async(false).await.map(|output| output.status)
+    }
+}
+
+impl<'a> CmdList<'a> {
+    /// Execute commands asynchronously one after another (see [`Cmd::output_async()`]), stops
+    /// on the first command which could not be executed
+    pub async fn output_async(&self) -> Result<Vec<CmdOutput>, CmdError> {
+        let mut v = Vec::new();
+        for cmd in &self.commands {
+            v.push(cmd.output_async().await?);
+        }
+        Ok(v)
+    }
+
+    /// Execute commands asynchronously one after another with inherited stdio (see
+    /// [`Cmd::status_async()`]), stops on the first command which could not be executed
+    pub async fn status_async(&self) -> Result<Vec<ExitStatus>, CmdError> {
+        let mut v = Vec::new();
+        for cmd in &self.commands {
+            v.push(cmd.status_async().await?);
+        }
+        Ok(v)
+    }
+}
+
+// create ready to exec [`tokio::process::Command`] from [`std::process::Command`]
+impl<'a> From<&Cmd<'a>> for tokio::process::Command {
+    fn from(cmd: &Cmd) -> Self {
+        tokio::process::Command::from(std::process::Command::from(cmd))
+    }
+}
+
+impl<'a> From<Cmd<'a>> for tokio::process::Command {
+    fn from(cmd: Cmd) -> Self {
+        tokio::process::Command::from(&cmd)
+    }
+}
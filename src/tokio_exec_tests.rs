@@ -0,0 +1,58 @@
+// `echo $GREETING` with `GREETING=hello`
+fn greeting_cmd() -> crate::Cmd<'static> {
+    use crate::Cmd;
+
+    let mut cmd = Cmd::with_name("sh");
+    cmd.env("GREETING", "hello").opt("-c", "echo $GREETING");
+    cmd
+}
+
+#[test]
+fn tokio_command() {
+    let cmd = greeting_cmd();
+    let std_command = std::process::Command::from(&cmd);
+    let command = cmd.to_tokio_command();
+    assert_eq!(command.as_std().get_program(), std_command.get_program());
+    assert_eq!(
+        command.as_std().get_args().collect::<Vec<_>>(),
+        std_command.get_args().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        command.as_std().get_envs().collect::<Vec<_>>(),
+        std_command.get_envs().collect::<Vec<_>>()
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn tokio_exec() {
+    let output = greeting_cmd().run_async().await.unwrap();
+    assert_eq!(output.stdout_lossy(), "hello\n");
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn tokio_exec_failed() {
+    use crate::Cmd;
+
+    let err = Cmd::with_name("false").run_async().await.unwrap_err();
+    assert_eq!(err.code(), Some(1));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn tokio_exec_cmd_list() {
+    use crate::{Cmd, CmdList};
+
+    let cmds = CmdList::new()
+        .cmd(Cmd::with_name("true").to_owned())
+        .cmd(Cmd::with_name("false").to_owned());
+    let outputs = cmds.output_async().await.unwrap();
+    assert_eq!(
+        outputs
+            .iter()
+            .map(|output| output.success())
+            .collect::<Vec<_>>(),
+        vec![true, false]
+    );
+}